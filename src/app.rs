@@ -1,20 +1,82 @@
 use crate::{
     config::Config,
     modules::{
-        build::BuildInfo,
-        coverage::CoverageInfo,
+        api::{self, GitHubApi, GitLabApi},
+        artifacts::ArtifactInfo,
+        build::{BuildAction, BuildInfo, JobInfo, RunFilter},
+        cargo::{CargoCommand, LocalBuild},
+        ci::CiProvider,
+        coverage::{
+            self, CoverageBaseline, CoverageInfo, CoverageRow, CoverageRun, CoverageSort, PatchCoverage, PatchScope,
+            ReportWatcher, SourceView,
+        },
         git::GitStatus,
+        insights::GitInsights,
+        logs::LogView,
+        notify::Notifier,
+        pulls::PullRequestInfo,
+        remote::RemoteInfo,
         system::SystemStats,
+        test_results::{self, TestCase, TestOutcome, TestReport, TestRun},
         timer::Timer,
     },
-    storage::TimerData,
+    storage::{CoverageHistory, CoverageSnapshot, TimerData},
 };
 
+pub const TABS: [&str; 6] = ["Dashboard", "Git", "Build", "Pull Requests", "Tests", "Coverage"];
+
+/// Pull requests change slowly and the query is comparatively expensive, so
+/// they are refreshed less often than the rest of the dashboard.
+pub const PULLS_REFRESH_SECS: u64 = 30;
+
 pub struct App {
     pub config: Config,
+    pub github: Option<GitHubApi>,
+    pub gitlab: Option<GitLabApi>,
+    pub ci: Option<CiProvider>,
     pub git_status: GitStatus,
+    pub insights: GitInsights,
     pub builds: Vec<BuildInfo>,
+    pub notifier: Notifier,
+    /// Artifacts of the drilled-into run (GitHub) or selected job (GitLab).
+    pub artifacts: Vec<ArtifactInfo>,
+    /// The run and job the artifacts were listed for.
+    pub artifacts_target: Option<(u64, Option<u64>)>,
+    pub selected_artifact: usize,
+    pub run_filter: RunFilter,
+    pub selected_build: usize,
+    pub jobs: Vec<JobInfo>,
+    pub jobs_run_id: Option<u64>,
+    pub selected_job: usize,
+    pub jobs_focused: bool,
+    pub log_view: Option<LogView>,
+    pub pending_action: Option<BuildAction>,
+    pub dispatch_index: usize,
+    pub local_command: CargoCommand,
+    pub local_build: Option<LocalBuild>,
+    pub test_run: Option<TestRun>,
+    pub test_report: Option<TestReport>,
+    pub previous_test_report: Option<TestReport>,
+    pub selected_test: usize,
+    pub pulls: Vec<PullRequestInfo>,
+    pub pulls_refreshed_at: Option<std::time::Instant>,
     pub coverage: CoverageInfo,
+    pub coverage_reports: ReportWatcher,
+    pub coverage_run: Option<CoverageRun>,
+    pub coverage_sort: CoverageSort,
+    /// Show the directory rollup instead of the flat file list.
+    pub coverage_tree: bool,
+    pub selected_coverage: usize,
+    pub source_view: Option<SourceView>,
+    pub coverage_history: CoverageHistory,
+    pub coverage_baseline: CoverageBaseline,
+    /// Where the current branch forked from the default branch.
+    pub merge_base: Option<String>,
+    pub patch_scope: PatchScope,
+    pub patch_coverage: Option<PatchCoverage>,
+    /// The canonical repository path, to shorten the absolute file names in
+    /// coverage reports.
+    pub repo_root: String,
     pub system_stats: SystemStats,
     pub timer: Timer,
     pub timer_data: TimerData,
@@ -27,14 +89,70 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(config: Config) -> anyhow::Result<Self> {
+    pub fn new(mut config: Config) -> anyhow::Result<Self> {
+        if let Some(remote) = RemoteInfo::detect(&config.repo_path) {
+            remote.apply_to(&mut config);
+        }
+        
         let timer_data = TimerData::load();
+        let insights = GitInsights::new(config.insights_weeks);
+        
+        let client = api::http_client(&config)?;
+        let github = config
+            .github_token
+            .as_ref()
+            .map(|token| GitHubApi::new(client.clone(), &config.github_api_url, token));
+        let gitlab = config
+            .gitlab_token
+            .as_ref()
+            .map(|token| GitLabApi::new(client.clone(), &config.gitlab_api_url, token));
+        let ci = CiProvider::from_config(&config, &client, github.as_ref());
+        let repo_root = std::fs::canonicalize(&config.repo_path)
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
         
         Ok(App {
             config,
+            github,
+            gitlab,
+            ci,
             git_status: GitStatus::default(),
+            insights,
             builds: Vec::new(),
+            notifier: Notifier::default(),
+            artifacts: Vec::new(),
+            artifacts_target: None,
+            selected_artifact: 0,
+            run_filter: RunFilter::MyBranch,
+            selected_build: 0,
+            jobs: Vec::new(),
+            jobs_run_id: None,
+            selected_job: 0,
+            jobs_focused: false,
+            log_view: None,
+            pending_action: None,
+            dispatch_index: 0,
+            local_command: CargoCommand::Build,
+            local_build: None,
+            test_run: None,
+            test_report: TestReport::load(),
+            previous_test_report: None,
+            selected_test: 0,
+            pulls: Vec::new(),
+            pulls_refreshed_at: None,
             coverage: CoverageInfo::default(),
+            coverage_reports: ReportWatcher::default(),
+            coverage_run: None,
+            coverage_sort: CoverageSort::default(),
+            coverage_tree: false,
+            selected_coverage: 0,
+            source_view: None,
+            coverage_history: CoverageHistory::load(),
+            coverage_baseline: CoverageBaseline::default(),
+            merge_base: None,
+            patch_scope: PatchScope::default(),
+            patch_coverage: None,
+            repo_root,
             system_stats: SystemStats::default(),
             timer: Timer::new(25),
             timer_data,
@@ -48,14 +166,8 @@ impl App {
     }
     
     pub fn project_name(&self) -> String {
-        if let Ok(repo) = git2::Repository::open(&self.config.repo_path) {
-            if let Ok(remote) = repo.find_remote("origin") {
-                if let Some(url) = remote.url() {
-                    if let Some(name) = url.split('/').last() {
-                        return name.trim_end_matches(".git").to_string();
-                    }
-                }
-            }
+        if let Some(remote) = RemoteInfo::detect(&self.config.repo_path) {
+            return remote.name().to_string();
         }
         
         if let Ok(repo) = git2::Repository::open(&self.config.repo_path) {
@@ -69,6 +181,180 @@ impl App {
         "rust-project".to_string()
     }
     
+    /// Branch to request runs for under the current filter.
+    pub fn run_branch(&self) -> Option<String> {
+        match self.run_filter {
+            RunFilter::MyBranch if self.git_status.branch != "unknown" => Some(self.git_status.branch.clone()),
+            RunFilter::MyBranch | RunFilter::All => None,
+            RunFilter::DefaultBranch => Some(self.git_status.default_branch.clone()),
+        }
+    }
+    
+    pub fn is_head_run(&self, run: &BuildInfo) -> bool {
+        !run.commit_sha.is_empty() && self.git_status.head_sha.starts_with(&run.commit_sha)
+    }
+    
+    pub fn selected_run(&self) -> Option<&BuildInfo> {
+        self.builds.get(self.selected_build)
+    }
+    
+    pub fn selected_job(&self) -> Option<&JobInfo> {
+        self.jobs.get(self.selected_job)
+    }
+    
+    pub fn select_next_build(&mut self) {
+        if self.jobs_focused {
+            if self.selected_job + 1 < self.jobs.len() {
+                self.selected_job += 1;
+            }
+        } else if self.selected_build + 1 < self.builds.len() {
+            self.selected_build += 1;
+        }
+    }
+    
+    pub fn select_prev_build(&mut self) {
+        if self.jobs_focused {
+            self.selected_job = self.selected_job.saturating_sub(1);
+        } else {
+            self.selected_build = self.selected_build.saturating_sub(1);
+        }
+    }
+    
+    /// Builds the dispatch action for the currently chosen preset from
+    /// `config.workflow_dispatch`, defaulting the ref to the checked-out branch.
+    pub fn dispatch_action(&self) -> Option<BuildAction> {
+        let preset = self.config.workflow_dispatch.get(self.dispatch_index)?;
+        Some(BuildAction::Dispatch {
+            workflow: preset.workflow.clone(),
+            git_ref: preset
+                .git_ref
+                .clone()
+                .unwrap_or_else(|| self.git_status.branch.clone()),
+            inputs: preset.inputs.clone(),
+        })
+    }
+    
+    /// The finished report replaces the current one, which is kept to tell
+    /// new failures from ones that were already failing. When CI results
+    /// are on screen, the last local run stays the baseline.
+    pub fn finish_test_run(&mut self, report: TestReport) {
+        self.test_run = None;
+        self.add_log(format!(
+            "{}: {} passed, {} failed, {} ignored",
+            report.harness.label(),
+            report.count(TestOutcome::Passed),
+            report.count(TestOutcome::Failed),
+            report.count(TestOutcome::Ignored)
+        ));
+        if let Err(e) = report.save() {
+            self.add_log(format!("Failed to save test results: {}", e));
+        }
+        let replaced = self.test_report.replace(report);
+        self.previous_test_report = test_results::next_baseline(replaced, self.previous_test_report.take());
+        self.selected_test = 0;
+    }
+    
+    pub fn finish_coverage_run(&mut self, result: anyhow::Result<CoverageInfo>) {
+        self.coverage_run = None;
+        match result {
+            Ok(coverage) => {
+                self.add_log(format!("cargo llvm-cov: {:.1}% line coverage", coverage.total_coverage()));
+                self.coverage = coverage;
+                self.record_coverage();
+            }
+            Err(e) => self.add_log(e.to_string()),
+        }
+    }
+    
+    /// Keeps a snapshot of locally produced coverage and warns about files
+    /// whose coverage dropped since the last one. Coverage from CI artifacts
+    /// belongs to another checkout and is not recorded.
+    pub fn record_coverage(&mut self) {
+        if self.coverage.source.is_some() || self.coverage.files.is_empty() {
+            return;
+        }
+        let snapshot = CoverageSnapshot::new(&self.coverage, &self.git_status.head_sha, &self.repo_root);
+        let regressed: Vec<String> = self
+            .coverage_history
+            .latest()
+            .map(|last| snapshot.regressions(last).into_iter().map(str::to_string).collect())
+            .unwrap_or_default();
+        if !self.coverage_history.record(snapshot) {
+            return;
+        }
+        if let Err(e) = self.coverage_history.save() {
+            self.add_log(format!("Failed to save coverage history: {}", e));
+        }
+        if !regressed.is_empty() {
+            self.add_log(format!("Coverage dropped in {} file(s): {}", regressed.len(), regressed.join(", ")));
+        }
+    }
+    
+    /// The snapshot per-file changes are measured against.
+    pub fn coverage_baseline_snapshot(&self) -> Option<&CoverageSnapshot> {
+        match self.coverage_baseline {
+            CoverageBaseline::Previous => self.coverage_history.previous(),
+            CoverageBaseline::MergeBase => self
+                .merge_base
+                .as_deref()
+                .and_then(|sha| self.coverage_history.at_commit(sha)),
+        }
+    }
+    
+    /// Shows test results read from a CI artifact. They are not saved, so
+    /// the next local run is still compared with the previous local run.
+    pub fn show_ci_tests(&mut self, source: &str, cases: Vec<TestCase>) {
+        let report = TestReport {
+            harness: self.config.test_harness,
+            finished_at: chrono::Utc::now(),
+            cases,
+            error: None,
+            source: Some(source.to_string()),
+        };
+        self.add_log(format!(
+            "{}: {} passed, {} failed, {} ignored",
+            source,
+            report.count(TestOutcome::Passed),
+            report.count(TestOutcome::Failed),
+            report.count(TestOutcome::Ignored)
+        ));
+        let replaced = self.test_report.replace(report);
+        self.previous_test_report = test_results::next_baseline(replaced, self.previous_test_report.take());
+        self.selected_test = 0;
+    }
+    
+    pub fn selected_artifact(&self) -> Option<&ArtifactInfo> {
+        self.artifacts.get(self.selected_artifact)
+    }
+    
+    pub fn selected_failure(&self) -> Option<&TestCase> {
+        self.test_report.as_ref()?.failures().get(self.selected_test).copied()
+    }
+    
+    /// Rows of the Coverage tab: every file in the chosen order, or the
+    /// directory rollup.
+    pub fn coverage_rows(&self) -> Vec<CoverageRow> {
+        if self.coverage_tree {
+            coverage::directory_tree(&self.coverage.files, &self.repo_root)
+        } else {
+            coverage::file_rows(&self.coverage.files, &self.repo_root, self.coverage_sort)
+        }
+    }
+    
+    /// Opens the file selected in the Coverage tab; directories of the
+    /// rollup have no source.
+    pub fn open_selected_source(&mut self) {
+        let rows = self.coverage_rows();
+        let selected = self.selected_coverage.min(rows.len().saturating_sub(1));
+        let Some(file) = rows.get(selected).and_then(|row| row.file) else {
+            return;
+        };
+        match SourceView::open(&self.coverage.files[file], &self.config.repo_path, &self.repo_root) {
+            Ok(view) => self.source_view = Some(view),
+            Err(e) => self.add_log(e.to_string()),
+        }
+    }
+    
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
@@ -85,22 +371,15 @@ impl Default for GitStatus {
     fn default() -> Self {
         GitStatus {
             branch: "unknown".to_string(),
+            default_branch: "main".to_string(),
+            head_sha: String::new(),
             commits_ahead: 0,
             commits_behind: 0,
-            staged: 0,
-            unstaged: 0,
-            untracked: 0,
+            changes: Default::default(),
             last_commit_message: String::new(),
             last_commit_author: String::new(),
-        }
-    }
-}
-
-impl Default for CoverageInfo {
-    fn default() -> Self {
-        CoverageInfo {
-            total_coverage: 0.0,
-            files: Vec::new(),
+            submodules: Vec::new(),
+            worktrees: Vec::new(),
         }
     }
 }
@@ -1,24 +1,124 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 
+use crate::modules::notify::NotifyBackend;
+use crate::modules::test_results::TestHarness;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default = "default_theme")]
     pub theme: String,
     pub github_token: Option<String>,
     pub gitlab_token: Option<String>,
+    #[serde(default = "default_github_api_url")]
+    pub github_api_url: String,
+    #[serde(default = "default_gitlab_api_url")]
+    pub gitlab_api_url: String,
+    /// PEM file with an extra root certificate, for instances behind a
+    /// private CA.
+    pub ca_cert_path: Option<String>,
+    pub proxy: Option<String>,
     #[serde(default = "default_repo_path")]
     pub repo_path: String,
     pub github_repo: Option<String>,
     pub gitlab_project_id: Option<String>,
     #[serde(default = "default_timer_minutes")]
     pub timer_default_minutes: u64,
+    #[serde(default = "default_insights_weeks")]
+    pub insights_weeks: usize,
+    #[serde(default)]
+    pub show_ignored: bool,
+    #[serde(default)]
+    pub workflow_dispatch: Vec<WorkflowDispatch>,
+    /// Where downloaded CI artifacts are unpacked, one directory each.
+    #[serde(default = "default_artifact_dir")]
+    pub artifact_dir: String,
+    /// lcov, Cobertura, tarpaulin or llvm-cov JSON reports, relative to
+    /// `repo_path`, to show instead of the default locations. Several
+    /// reports are merged, e.g. one per subproject.
+    #[serde(default)]
+    pub coverage_reports: Vec<String>,
+    #[serde(default)]
+    pub coverage_thresholds: CoverageThresholds,
+    #[serde(default)]
+    pub test_harness: TestHarness,
+    /// How to announce CI runs on the current branch finishing.
+    #[serde(default)]
+    pub notify: NotifyBackend,
+    pub notify_command: Option<String>,
+    pub gitea: Option<GiteaConfig>,
+    pub jenkins: Option<JenkinsConfig>,
+    pub woodpecker: Option<WoodpeckerConfig>,
+}
+
+/// A workflow that can be triggered from the Build tab. On GitHub `workflow`
+/// is the workflow file name or id; on GitLab it is only used as a label and
+/// `inputs` are passed as pipeline variables. `ref` defaults to the current
+/// branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowDispatch {
+    pub workflow: String,
+    #[serde(default, rename = "ref")]
+    pub git_ref: Option<String>,
+    #[serde(default)]
+    pub inputs: HashMap<String, String>,
+}
+
+/// Coverage below `low` percent is shown in red, below `high` in yellow
+/// and green otherwise.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CoverageThresholds {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Default for CoverageThresholds {
+    fn default() -> Self {
+        CoverageThresholds { low: 50.0, high: 80.0 }
+    }
+}
+
+/// A Gitea or Forgejo instance. `url` is the API root, e.g.
+/// `https://codeberg.org/api/v1`; `repo` is detected from the remote when
+/// left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiteaConfig {
+    pub url: String,
+    pub token: Option<String>,
+    pub repo: Option<String>,
+}
+
+/// `job` is the job name, with folders separated by `/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JenkinsConfig {
+    pub url: String,
+    pub job: String,
+    pub user: Option<String>,
+    pub token: Option<String>,
+}
+
+/// `repo` is the forge's `owner/name`; Woodpecker's numeric repository id is
+/// looked up from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WoodpeckerConfig {
+    pub url: String,
+    pub token: String,
+    pub repo: String,
 }
 
 fn default_theme() -> String {
     "moonlight".to_string()
 }
 
+fn default_github_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+fn default_gitlab_api_url() -> String {
+    "https://gitlab.com/api/v4".to_string()
+}
+
 fn default_repo_path() -> String {
     ".".to_string()
 }
@@ -27,6 +127,14 @@ fn default_timer_minutes() -> u64 {
     25
 }
 
+fn default_insights_weeks() -> usize {
+    12
+}
+
+fn default_artifact_dir() -> String {
+    "artifacts".to_string()
+}
+
 impl Config {
     pub fn load() -> Self {
         match fs::read_to_string("config.json") {
@@ -53,10 +161,26 @@ impl Default for Config {
             theme: "moonlight".to_string(),
             github_token: None,
             gitlab_token: None,
+            github_api_url: default_github_api_url(),
+            gitlab_api_url: default_gitlab_api_url(),
+            ca_cert_path: None,
+            proxy: None,
             repo_path: ".".to_string(),
             github_repo: None,
             gitlab_project_id: None,
             timer_default_minutes: 25,
+            insights_weeks: 12,
+            show_ignored: false,
+            workflow_dispatch: Vec::new(),
+            artifact_dir: default_artifact_dir(),
+            coverage_reports: Vec::new(),
+            coverage_thresholds: CoverageThresholds::default(),
+            test_harness: TestHarness::Cargo,
+            notify: NotifyBackend::Bell,
+            notify_command: None,
+            gitea: None,
+            jenkins: None,
+            woodpecker: None,
         }
     }
 }
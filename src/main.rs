@@ -14,11 +14,17 @@ mod storage;
 mod ui;
 
 use app::App;
+use modules::logs::LogView;
 use config::Config;
 use modules::{
-    build::BuildModule,
-    coverage::CoverageModule,
+    artifacts::{self, ArtifactReport},
+    build::BuildAction,
+    cargo::LocalBuild,
+    test_results::TestRun,
+    coverage::{CoverageInfo, CoverageRun, PatchCoverage, PatchScope},
     git::GitModule,
+    notify,
+    pulls::PullsModule,
     system::SystemModule,
 };
 use ui::{layout, themes::ThemeManager};
@@ -40,18 +46,38 @@ async fn main() -> Result<()> {
     refresh_data(&mut app, &mut system_module).await;
     
     loop {
+        if let Some(local) = app.local_build.as_mut() {
+            if local.poll() {
+                let summary = local.summary();
+                app.add_log(summary);
+            }
+        }
+        if let Some(report) = app.test_run.as_mut().and_then(|run| run.poll()) {
+            app.finish_test_run(report);
+        }
+        if let Some(result) = app.coverage_run.as_mut().and_then(|run| run.poll()) {
+            app.finish_coverage_run(result);
+        }
+        
         terminal.draw(|f| {
             layout::render_dashboard(f, &app, theme_manager.current_theme());
         })?;
         
         if crossterm::event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+                if key.kind == KeyEventKind::Press && app.pending_action.is_some() {
+                    handle_confirm_key(&mut app, key.code).await;
+                } else if key.kind == KeyEventKind::Press && app.log_view.is_some() {
+                    handle_log_key(&mut app, key.code);
+                } else if key.kind == KeyEventKind::Press && app.source_view.is_some() {
+                    handle_source_key(&mut app, key.code);
+                } else if key.kind == KeyEventKind::Press {
                     match key.code {
                         KeyCode::Char('q') | KeyCode::Char('Q') => break,
                         KeyCode::Esc => app.quit(),
-                        KeyCode::Tab => app.current_tab = (app.current_tab + 1) % 4,
+                        KeyCode::Tab => app.current_tab = (app.current_tab + 1) % app::TABS.len(),
                         KeyCode::Char('r') | KeyCode::Char('R') => {
+                            app.pulls_refreshed_at = None;
                             refresh_data(&mut app, &mut system_module).await;
                         }
                         KeyCode::Char(' ') => {
@@ -60,6 +86,80 @@ async fn main() -> Result<()> {
                         KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                             theme_manager.cycle_theme();
                         }
+                        KeyCode::Down if app.current_tab == 2 => app.select_next_build(),
+                        KeyCode::Up if app.current_tab == 2 => app.select_prev_build(),
+                        KeyCode::Left | KeyCode::Right if app.current_tab == 2 => {
+                            app.jobs_focused = !app.jobs_focused && !app.jobs.is_empty();
+                        }
+                        KeyCode::Char('a') if app.current_tab == 2 => {
+                            app.pending_action = app.selected_run().map(|run| BuildAction::Rerun(run.id));
+                        }
+                        KeyCode::Char('f') if app.current_tab == 2 => {
+                            app.pending_action = app.selected_run().map(|run| BuildAction::RerunFailed(run.id));
+                        }
+                        KeyCode::Char('c') if app.current_tab == 2 => {
+                            app.pending_action = app.selected_run().map(|run| BuildAction::Cancel(run.id));
+                        }
+                        KeyCode::Char('b') if app.current_tab == 2 => {
+                            app.run_filter = app.run_filter.next();
+                            app.add_log(format!("Showing runs for {}", app.run_filter.label()));
+                            refresh_data(&mut app, &mut system_module).await;
+                        }
+                        KeyCode::Char('d') if app.current_tab == 2 => {
+                            app.pending_action = app.dispatch_action();
+                            if app.pending_action.is_none() {
+                                app.add_log("No workflow_dispatch presets configured".to_string());
+                            }
+                        }
+                        KeyCode::Char('k') if app.current_tab == 2 => {
+                            app.local_command = app.local_command.next();
+                        }
+                        KeyCode::Char('l') if app.current_tab == 2 => toggle_local_build(&mut app),
+                        KeyCode::Char('x') if app.current_tab == 2 => list_artifacts(&mut app).await,
+                        KeyCode::Char('s') if app.current_tab == 2 => download_artifact(&mut app).await,
+                        KeyCode::Char('t') if app.current_tab == 4 => toggle_test_run(&mut app),
+                        KeyCode::Down if app.current_tab == 4 => {
+                            let failures = app.test_report.as_ref().map_or(0, |report| report.failures().len());
+                            if app.selected_test + 1 < failures {
+                                app.selected_test += 1;
+                            }
+                        }
+                        KeyCode::Up if app.current_tab == 4 => {
+                            app.selected_test = app.selected_test.saturating_sub(1);
+                        }
+                        KeyCode::Char('v') if app.current_tab == 5 => toggle_coverage_run(&mut app),
+                        KeyCode::Char('o') if app.current_tab == 5 => {
+                            app.coverage_sort = app.coverage_sort.next();
+                            app.coverage_tree = false;
+                        }
+                        KeyCode::Char('p') if app.current_tab == 5 => {
+                            app.patch_scope = app.patch_scope.next();
+                            update_patch_coverage(&mut app);
+                        }
+                        KeyCode::Char('b') if app.current_tab == 5 => {
+                            app.coverage_baseline = app.coverage_baseline.next();
+                        }
+                        KeyCode::Char('g') if app.current_tab == 5 => {
+                            app.coverage_tree = !app.coverage_tree;
+                            app.selected_coverage = 0;
+                        }
+                        KeyCode::Down if app.current_tab == 5 && app.selected_coverage + 1 < app.coverage_rows().len() => {
+                            app.selected_coverage += 1;
+                        }
+                        KeyCode::Up if app.current_tab == 5 => {
+                            app.selected_coverage = app.selected_coverage.saturating_sub(1);
+                        }
+                        KeyCode::Enter if app.current_tab == 5 => app.open_selected_source(),
+                        KeyCode::Enter if app.current_tab == 2 && app.jobs_focused => {
+                            open_job_log(&mut app).await;
+                        }
+                        KeyCode::Enter if app.current_tab == 2 => {
+                            app.artifacts.clear();
+                            app.jobs.clear();
+                            app.jobs_run_id = app.selected_run().map(|run| run.id);
+                            app.selected_job = 0;
+                            load_jobs(&mut app).await;
+                        }
                         _ => {}
                     }
                 }
@@ -85,23 +185,59 @@ async fn refresh_data(app: &mut App, system_module: &mut SystemModule) {
         app.system_stats = stats;
     }
     
-    if let Ok(git_status) = GitModule::get_status(&app.config.repo_path) {
+    if let Ok(git_status) = GitModule::get_status(&app.config.repo_path, app.config.show_ignored) {
         app.git_status = git_status;
     }
+    app.merge_base = GitModule::merge_base(&app.config.repo_path, &app.git_status.default_branch);
     
-    if let Ok(coverage) = CoverageModule::get_coverage() {
-        app.coverage = coverage;
+    let repo_path = app.config.repo_path.clone();
+    if let Err(e) = app.insights.update(&repo_path) {
+        app.add_log(format!("Git insights failed: {}", e));
     }
     
-    if let (Some(repo), Some(token)) = (&app.config.github_repo, &app.config.github_token) {
-        if let Ok(builds) = BuildModule::get_github_status(repo, token).await {
-            app.builds = builds;
+    // Coverage loaded from a CI artifact is not replaced by local reports on
+    // disk; only a local `cargo llvm-cov` run (`v`) takes its place.
+    if app.coverage.source.is_none() {
+        match app.coverage_reports.poll(&app.config.repo_path, &app.config.coverage_reports) {
+            Some(Ok(coverage)) => {
+                app.coverage = coverage;
+                app.record_coverage();
+            }
+            Some(Err(e)) => app.add_log(format!("Failed to read coverage report: {}", e)),
+            None => {}
         }
-    } else if let (Some(project_id), Some(token)) = (&app.config.gitlab_project_id, &app.config.gitlab_token) {
-        if let Ok(builds) = BuildModule::get_gitlab_status(project_id, token).await {
+    }
+    update_patch_coverage(app);
+    
+    let branch = app.run_branch();
+    let selected_run = app.selected_run().map(|run| run.id);
+    if let Some(ci) = &app.ci {
+        if let Ok(builds) = ci.runs(branch.as_deref()).await {
             app.builds = builds;
         }
     }
+    notify_transitions(app).await;
+    // New runs arrive at the top; stay on the run that was selected.
+    app.selected_build = selected_run
+        .and_then(|id| app.builds.iter().position(|run| run.id == id))
+        .unwrap_or(app.selected_build)
+        .min(app.builds.len().saturating_sub(1));
+    
+    let pulls_due = match app.pulls_refreshed_at {
+        Some(at) => at.elapsed().as_secs() >= app::PULLS_REFRESH_SECS,
+        None => true,
+    };
+    if pulls_due {
+        refresh_pulls(app).await;
+    }
+    
+    let drilled_run_active = app
+        .builds
+        .iter()
+        .any(|run| Some(run.id) == app.jobs_run_id && run.status.is_active());
+    if drilled_run_active {
+        load_jobs(app).await;
+    }
     
     let completed = app.timer.update();
     if completed {
@@ -120,6 +256,312 @@ async fn refresh_data(app: &mut App, system_module: &mut SystemModule) {
     }
 }
 
+/// Notifications follow the checked-out branch whatever the run filter
+/// shows, so runs for it are fetched separately when the filter leaves
+/// them out.
+async fn notify_transitions(app: &mut App) {
+    let branch = app.git_status.branch.clone();
+    let notifications = match (app.run_branch(), &app.ci) {
+        (Some(shown), Some(ci)) if shown != branch && branch != "unknown" => match ci.runs(Some(&branch)).await {
+            Ok(builds) => app.notifier.observe(&builds, &branch),
+            Err(_) => Vec::new(),
+        },
+        _ => app.notifier.observe(&app.builds, &branch),
+    };
+    for notification in notifications {
+        app.add_log(format!("{}: {}", notification.title, notification.body));
+        if let Err(e) = notify::send(app.config.notify, app.config.notify_command.as_deref(), &notification) {
+            app.add_log(format!("Failed to send notification: {}", e));
+        }
+    }
+}
+
+async fn refresh_pulls(app: &mut App) {
+    let branch = app.git_status.branch.clone();
+    let pulls = if let (Some(api), Some(repo)) = (&app.github, &app.config.github_repo) {
+        PullsModule::get_github_pulls(api, repo, &branch).await
+    } else if let (Some(api), Some(project_id)) = (&app.gitlab, &app.config.gitlab_project_id) {
+        PullsModule::get_gitlab_merge_requests(api, project_id, &branch).await
+    } else {
+        return;
+    };
+    
+    app.pulls_refreshed_at = Some(std::time::Instant::now());
+    match pulls {
+        Ok(pulls) => app.pulls = pulls,
+        Err(e) => app.add_log(format!("Failed to load pull requests: {}", e)),
+    }
+}
+
+async fn load_jobs(app: &mut App) {
+    let Some(run_id) = app.jobs_run_id else {
+        return;
+    };
+    
+    let Some(ci) = &app.ci else {
+        return;
+    };
+    let jobs = ci.jobs(run_id).await;
+    
+    match jobs {
+        Ok(jobs) => {
+            app.selected_job = app.selected_job.min(jobs.len().saturating_sub(1));
+            app.jobs = jobs;
+        }
+        Err(e) => app.add_log(format!("Failed to load jobs: {}", e)),
+    }
+}
+
+async fn open_job_log(app: &mut App) {
+    let (Some(ci), Some(run_id), Some(job)) = (&app.ci, app.jobs_run_id, app.selected_job()) else {
+        return;
+    };
+    let title = job.name.clone();
+    let log = ci.job_log(run_id, job.id).await;
+    
+    match log {
+        Ok(raw) => app.log_view = Some(LogView::new(title, &raw)),
+        Err(e) => app.add_log(format!("Failed to load log: {}", e)),
+    }
+}
+
+/// Lists the artifacts of the drilled-into run, or of the selected job on
+/// GitLab. Pressing the key again for the same target selects the next one.
+async fn list_artifacts(app: &mut App) {
+    let Some(run_id) = app.jobs_run_id.or_else(|| app.selected_run().map(|run| run.id)) else {
+        return;
+    };
+    let job_id = app.selected_job().filter(|_| app.jobs_run_id.is_some()).map(|job| job.id);
+    let target = Some((run_id, job_id));
+    if app.artifacts_target == target && !app.artifacts.is_empty() {
+        app.selected_artifact = (app.selected_artifact + 1) % app.artifacts.len();
+        return;
+    }
+    
+    let Some(ci) = &app.ci else {
+        return;
+    };
+    match ci.artifacts(run_id, job_id).await {
+        Ok(artifacts) => {
+            if artifacts.is_empty() {
+                app.add_log(format!("No artifacts for run #{}", run_id));
+            }
+            app.artifacts = artifacts;
+            app.artifacts_target = target;
+            app.selected_artifact = 0;
+        }
+        Err(e) => app.add_log(format!("Failed to list artifacts: {}", e)),
+    }
+}
+
+/// Downloads the selected artifact into `artifact_dir` and shows any
+/// coverage or test reports found in it.
+async fn download_artifact(app: &mut App) {
+    let (Some(ci), Some(artifact)) = (&app.ci, app.selected_artifact()) else {
+        return;
+    };
+    if artifact.expired {
+        app.add_log(format!("Artifact {} has expired", artifact.name));
+        return;
+    }
+    let artifact = artifact.clone();
+    let archive = match ci.download_artifact(&artifact).await {
+        Ok(archive) => archive,
+        Err(e) => {
+            app.add_log(format!("Failed to download {}: {}", artifact.name, e));
+            return;
+        }
+    };
+    
+    let dir = artifacts::target_dir(std::path::Path::new(&app.config.artifact_dir), &artifact);
+    let files = match artifacts::extract(&archive, &dir) {
+        Ok(files) => files,
+        Err(e) => {
+            app.add_log(format!("Failed to unpack {}: {}", artifact.name, e));
+            return;
+        }
+    };
+    app.add_log(format!("Saved {} files to {}", files.len(), dir.display()));
+    
+    let mut cases = Vec::new();
+    let mut coverage: Option<CoverageInfo> = None;
+    for file in &files {
+        match artifacts::read_report(file) {
+            Some(ArtifactReport::Coverage(found)) => match &mut coverage {
+                Some(coverage) => coverage.merge(found),
+                None => coverage = Some(found),
+            },
+            Some(ArtifactReport::Tests(found)) => cases.extend(found),
+            None => {}
+        }
+    }
+    if let Some(mut coverage) = coverage {
+        app.add_log(format!("Coverage from {}: {:.1}%", artifact.name, coverage.total_coverage()));
+        coverage.source = Some(artifact.name.clone());
+        app.coverage = coverage;
+    }
+    if !cases.is_empty() {
+        app.show_ci_tests(&artifact.name, cases);
+    }
+}
+
+fn toggle_local_build(app: &mut App) {
+    match app.local_build.as_mut() {
+        Some(local) if local.is_running() => {
+            local.cancel();
+            let label = local.command.label();
+            app.add_log(format!("{} cancelled", label));
+        }
+        _ => {
+            app.local_build = Some(LocalBuild::spawn(app.local_command, &app.config.repo_path));
+            app.add_log(format!("Running {}", app.local_command.label()));
+        }
+    }
+}
+
+fn toggle_test_run(app: &mut App) {
+    if let Some(mut run) = app.test_run.take() {
+        run.cancel();
+        app.add_log(format!("{} cancelled", run.harness.label()));
+    } else {
+        let harness = app.config.test_harness;
+        app.test_run = Some(TestRun::spawn(harness, &app.config.repo_path));
+        app.add_log(format!("Running {}", harness.label()));
+    }
+}
+
+/// Branch scope compares with the merge-base, so it has nothing to show
+/// until the default branch is known locally.
+fn update_patch_coverage(app: &mut App) {
+    let base = match app.patch_scope {
+        PatchScope::WorkingTree => None,
+        PatchScope::Branch => match &app.merge_base {
+            Some(sha) => Some(sha.as_str()),
+            None => {
+                app.patch_coverage = None;
+                return;
+            }
+        },
+    };
+    app.patch_coverage = GitModule::changed_lines(&app.config.repo_path, base)
+        .ok()
+        .map(|changed| PatchCoverage::new(&app.coverage, &changed, &app.repo_root));
+}
+
+fn toggle_coverage_run(app: &mut App) {
+    if let Some(mut run) = app.coverage_run.take() {
+        run.cancel();
+        app.add_log("cargo llvm-cov cancelled".to_string());
+    } else {
+        app.coverage_run = Some(CoverageRun::spawn(&app.config.repo_path));
+        app.add_log("Running cargo llvm-cov".to_string());
+    }
+}
+
+async fn handle_confirm_key(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => run_build_action(app).await,
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.pending_action = None,
+        KeyCode::Left | KeyCode::Right if matches!(app.pending_action, Some(BuildAction::Dispatch { .. })) => {
+            let presets = app.config.workflow_dispatch.len();
+            app.dispatch_index = if code == KeyCode::Right {
+                (app.dispatch_index + 1) % presets
+            } else {
+                (app.dispatch_index + presets - 1) % presets
+            };
+            app.pending_action = app.dispatch_action();
+        }
+        _ => {}
+    }
+}
+
+async fn run_build_action(app: &mut App) {
+    let Some(action) = app.pending_action.take() else {
+        return;
+    };
+    
+    let Some(ci) = &app.ci else {
+        return;
+    };
+    let result = ci.run_action(&action).await;
+    let expected = ci.expected_status(&action);
+    
+    match result {
+        Ok(()) => {
+            app.add_log(format!("{}: requested", action.describe()));
+            if let (Some(run_id), Some(status)) = (action.run_id(), expected) {
+                if let Some(run) = app.builds.iter_mut().find(|run| run.id == run_id) {
+                    run.status = status;
+                }
+            }
+        }
+        Err(e) => app.add_log(format!("{} failed: {}", action.describe(), e)),
+    }
+}
+
+fn handle_log_key(app: &mut App, code: KeyCode) {
+    let Some(view) = app.log_view.as_mut() else {
+        return;
+    };
+    
+    if view.searching {
+        match code {
+            KeyCode::Enter => {
+                view.searching = false;
+                view.search();
+            }
+            KeyCode::Esc => {
+                view.searching = false;
+                view.query.clear();
+                view.matches.clear();
+            }
+            KeyCode::Backspace => {
+                view.query.pop();
+            }
+            KeyCode::Char(c) => view.query.push(c),
+            _ => {}
+        }
+        return;
+    }
+    
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.log_view = None,
+        KeyCode::Down | KeyCode::Char('j') => view.scroll_down(1),
+        KeyCode::Up | KeyCode::Char('k') => view.scroll_up(1),
+        KeyCode::PageDown => view.scroll_down(20),
+        KeyCode::PageUp => view.scroll_up(20),
+        KeyCode::Home | KeyCode::Char('g') => view.scroll = 0,
+        KeyCode::End | KeyCode::Char('G') => view.scroll_down(view.lines.len()),
+        KeyCode::Char('/') => {
+            view.searching = true;
+            view.query.clear();
+        }
+        KeyCode::Char('n') => view.next_match(),
+        KeyCode::Char('N') => view.prev_match(),
+        KeyCode::Char('e') => view.jump_to_first_error(),
+        _ => {}
+    }
+}
+
+fn handle_source_key(app: &mut App, code: KeyCode) {
+    let Some(view) = app.source_view.as_mut() else {
+        return;
+    };
+    
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.source_view = None,
+        KeyCode::Down | KeyCode::Char('j') => view.scroll_down(1),
+        KeyCode::Up | KeyCode::Char('k') => view.scroll_up(1),
+        KeyCode::PageDown => view.scroll_down(20),
+        KeyCode::PageUp => view.scroll_up(20),
+        KeyCode::Home | KeyCode::Char('g') => view.scroll = 0,
+        KeyCode::End | KeyCode::Char('G') => view.scroll_down(view.lines.len()),
+        KeyCode::Char('n') => view.next_uncovered(),
+        KeyCode::Char('N') => view.prev_uncovered(),
+        _ => {}
+    }
+}
+
 fn handle_timer(app: &mut App) {
     match app.timer.state() {
         crate::modules::timer::TimerState::Idle => {
@@ -0,0 +1,540 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{HeaderMap, ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use crate::config::Config;
+
+/// Builds the HTTP client shared by every provider, honouring the configured
+/// proxy and extra CA certificate for self-hosted instances.
+pub fn http_client(config: &Config) -> Result<Client> {
+    let mut builder = Client::builder().user_agent("devdash");
+    
+    if let Some(path) = &config.ca_cert_path {
+        let pem = fs::read(path).with_context(|| format!("reading CA certificate {}", path))?;
+        builder = builder.add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+    }
+    if let Some(proxy) = &config.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    
+    Ok(builder.build()?)
+}
+
+/// REST quota as last reported by GitHub's `X-RateLimit-*` headers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimit {
+    pub remaining: Option<u32>,
+    pub limit: Option<u32>,
+    pub reset_at: Option<DateTime<Utc>>,
+    /// No requests are sent before this time.
+    pub blocked_until: Option<DateTime<Utc>>,
+}
+
+impl RateLimit {
+    /// Records the quota headers of a response. An exhausted quota blocks
+    /// until it resets; `Retry-After` (sent with secondary rate limits)
+    /// blocks for as long as it asks.
+    fn update(&mut self, status: StatusCode, headers: &HeaderMap, now: DateTime<Utc>) {
+        let number = |name: &str| headers.get(name)?.to_str().ok()?.parse::<i64>().ok();
+        
+        if let Some(remaining) = number("x-ratelimit-remaining") {
+            self.remaining = Some(remaining as u32);
+        }
+        if let Some(limit) = number("x-ratelimit-limit") {
+            self.limit = Some(limit as u32);
+        }
+        if let Some(reset) = number("x-ratelimit-reset") {
+            self.reset_at = DateTime::from_timestamp(reset, 0);
+        }
+        
+        let limited = status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS;
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<i64>().ok());
+        self.blocked_until = match (retry_after, self.remaining) {
+            (Some(seconds), _) if limited => Some(now + Duration::seconds(seconds)),
+            (_, Some(0)) => self.reset_at.or(Some(now + Duration::seconds(60))),
+            _ => None,
+        };
+    }
+    
+    pub fn is_blocked(&self, now: DateTime<Utc>) -> bool {
+        self.blocked_until.is_some_and(|until| until > now)
+    }
+    
+    fn check(&self, now: DateTime<Utc>) -> Result<()> {
+        match self.blocked_until {
+            Some(until) if until > now => {
+                Err(anyhow!("GitHub rate limit reached, waiting until {}", until.format("%H:%M:%S")))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Responses kept for revalidation. Runs, jobs and artifacts of runs that
+/// are no longer looked at pile up over a session, so the least recently
+/// requested are dropped beyond this many.
+const RESPONSE_CACHE_LIMIT: usize = 64;
+
+#[derive(Debug)]
+struct CachedResponse {
+    etag: String,
+    body: String,
+    last_used: u64,
+}
+
+/// Bodies of earlier responses keyed by URL, with the ETag they came with.
+#[derive(Debug, Default)]
+struct ResponseCache {
+    entries: HashMap<String, CachedResponse>,
+    requests: u64,
+    rate_limit: RateLimit,
+}
+
+impl ResponseCache {
+    fn get(&mut self, key: &str) -> Option<&CachedResponse> {
+        self.requests += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.requests;
+        Some(entry)
+    }
+    
+    fn insert(&mut self, key: String, etag: String, body: String) {
+        let last_used = self.requests;
+        self.entries.insert(key, CachedResponse { etag, body, last_used });
+        if self.entries.len() > RESPONSE_CACHE_LIMIT {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Clones share the response cache and the rate-limit state.
+#[derive(Debug, Clone)]
+pub struct GitHubApi {
+    client: Client,
+    base_url: String,
+    token: String,
+    cache: Arc<Mutex<ResponseCache>>,
+}
+
+impl GitHubApi {
+    pub fn new(client: Client, base_url: &str, token: &str) -> Self {
+        GitHubApi {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            cache: Arc::default(),
+        }
+    }
+    
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+    
+    /// github.com serves GraphQL at `/graphql` next to the REST root, while
+    /// GitHub Enterprise serves REST at `/api/v3` and GraphQL at `/api/graphql`.
+    pub fn graphql_url(&self) -> String {
+        match self.base_url.strip_suffix("/v3") {
+            Some(api_root) => format!("{}/graphql", api_root),
+            None => format!("{}/graphql", self.base_url),
+        }
+    }
+    
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.get(self.url(path)))
+    }
+    
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.post(self.url(path)))
+    }
+    
+    pub fn graphql(&self) -> RequestBuilder {
+        self.authorize(self.client.post(self.graphql_url()))
+    }
+    
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        request
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+    }
+    
+    pub fn rate_limit(&self) -> RateLimit {
+        self.cache.lock().unwrap().rate_limit.clone()
+    }
+    
+    /// Sends a GET built with `get` and returns its body. Requests repeat the
+    /// ETag of the last response for the same URL, so an unchanged resource
+    /// comes back as a 304 that does not count against the quota. Nothing
+    /// is sent while the quota is exhausted.
+    pub async fn fetch(&self, request: RequestBuilder) -> Result<String> {
+        let mut request = request.build()?;
+        let key = request.url().to_string();
+        
+        let etag = {
+            let mut cache = self.cache.lock().unwrap();
+            cache.rate_limit.check(Utc::now())?;
+            cache.get(&key).map(|entry| entry.etag.clone())
+        };
+        if let Some(etag) = etag {
+            request.headers_mut().insert(IF_NONE_MATCH, etag.parse()?);
+        }
+        
+        let response = self.client.execute(request).await?;
+        let status = response.status();
+        {
+            let mut cache = self.cache.lock().unwrap();
+            cache.rate_limit.update(status, response.headers(), Utc::now());
+            if status == StatusCode::NOT_MODIFIED {
+                if let Some(entry) = cache.entries.get(&key) {
+                    return Ok(entry.body.clone());
+                }
+            }
+        }
+        
+        let response = response.error_for_status()?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+        if let Some(etag) = etag {
+            self.cache.lock().unwrap().insert(key, etag, body.clone());
+        }
+        Ok(body)
+    }
+    
+    /// Sends a request whose response should not be cached (actions, logs,
+    /// downloads). Like `fetch` it records the quota headers and sends
+    /// nothing while the quota is exhausted.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        self.cache.lock().unwrap().rate_limit.check(Utc::now())?;
+        let response = request.send().await?;
+        self.cache
+            .lock()
+            .unwrap()
+            .rate_limit
+            .update(response.status(), response.headers(), Utc::now());
+        Ok(response.error_for_status()?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitLabApi {
+    client: Client,
+    base_url: String,
+    token: String,
+}
+
+impl GitLabApi {
+    pub fn new(client: Client, base_url: &str, token: &str) -> Self {
+        GitLabApi {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+    
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+    
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.client.get(self.url(path)).header("PRIVATE-TOKEN", &self.token)
+    }
+    
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.client.post(self.url(path)).header("PRIVATE-TOKEN", &self.token)
+    }
+}
+
+/// Gitea and Forgejo share the same API, rooted at `/api/v1`. Public
+/// repositories can be read without a token.
+#[derive(Debug, Clone)]
+pub struct GiteaApi {
+    client: Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl GiteaApi {
+    pub fn new(client: Client, base_url: &str, token: Option<&str>) -> Self {
+        GiteaApi {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.map(str::to_string),
+        }
+    }
+    
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+    
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.get(self.url(path)))
+    }
+    
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.post(self.url(path)))
+    }
+    
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.header("Authorization", format!("token {}", token)),
+            None => request,
+        }
+    }
+}
+
+/// Jenkins paths are relative to the configured job, so `get("/api/json")`
+/// reads the job itself and `get("/42/consoleText")` one of its builds.
+#[derive(Debug, Clone)]
+pub struct JenkinsApi {
+    client: Client,
+    job_url: String,
+    user: Option<String>,
+    token: Option<String>,
+}
+
+impl JenkinsApi {
+    /// `job` may name a job inside folders, e.g. `team/app`, which Jenkins
+    /// serves at `/job/team/job/app`.
+    pub fn new(client: Client, base_url: &str, job: &str, user: Option<&str>, token: Option<&str>) -> Self {
+        let job_path: String = job
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| format!("/job/{}", segment))
+            .collect();
+        JenkinsApi {
+            client,
+            job_url: format!("{}{}", base_url.trim_end_matches('/'), job_path),
+            user: user.map(str::to_string),
+            token: token.map(str::to_string),
+        }
+    }
+    
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.job_url, path)
+    }
+    
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.get(self.url(path)))
+    }
+    
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.client.post(self.url(path)))
+    }
+    
+    /// API tokens are sent as the basic-auth password, which also exempts
+    /// POSTs from Jenkins' CSRF crumb check.
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.user {
+            Some(user) => request.basic_auth(user, self.token.as_ref()),
+            None => request,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WoodpeckerApi {
+    client: Client,
+    base_url: String,
+    token: String,
+}
+
+impl WoodpeckerApi {
+    pub fn new(client: Client, base_url: &str, token: &str) -> Self {
+        WoodpeckerApi {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+    
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/api{}", self.base_url, path)
+    }
+    
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.client.get(self.url(path)).bearer_auth(&self.token)
+    }
+    
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.client.post(self.url(path)).bearer_auth(&self.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    #[tokio::test]
+    async fn requests_are_routed_through_configured_proxy() {
+        let proxy = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ping"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("pong"))
+            .expect(1)
+            .mount(&proxy)
+            .await;
+        
+        let config = Config {
+            proxy: Some(proxy.uri()),
+            ..Config::default()
+        };
+        let client = http_client(&config).unwrap();
+        let body = client
+            .get("http://devdash.invalid/ping")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(body, "pong");
+    }
+    
+    #[test]
+    fn invalid_ca_certificate_is_rejected() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cert = dir.path().join("ca.pem");
+        fs::write(&cert, "not a certificate").unwrap();
+        
+        let config = Config {
+            ca_cert_path: Some(cert.to_string_lossy().to_string()),
+            ..Config::default()
+        };
+        assert!(http_client(&config).is_err());
+    }
+    
+    #[test]
+    fn graphql_url_follows_enterprise_layout() {
+        let client = Client::new();
+        let dotcom = GitHubApi::new(client.clone(), "https://api.github.com/", "t");
+        let enterprise = GitHubApi::new(client, "https://ghe.example.com/api/v3", "t");
+        
+        assert_eq!(dotcom.url("/repos/a/b"), "https://api.github.com/repos/a/b");
+        assert_eq!(dotcom.graphql_url(), "https://api.github.com/graphql");
+        assert_eq!(enterprise.graphql_url(), "https://ghe.example.com/api/graphql");
+    }
+    
+    #[tokio::test]
+    async fn github_fetch_revalidates_with_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/a/b/actions/runs"))
+            .and(header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304).insert_header("x-ratelimit-remaining", "4999"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/a/b/actions/runs"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .insert_header("x-ratelimit-remaining", "4998")
+                    .insert_header("x-ratelimit-limit", "5000")
+                    .set_body_string("{\"workflow_runs\":[]}"),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        
+        let api = GitHubApi::new(Client::new(), &server.uri(), "t");
+        let first = api.fetch(api.get("/repos/a/b/actions/runs")).await.unwrap();
+        assert_eq!(api.rate_limit().remaining, Some(4998));
+        
+        let second = api.fetch(api.get("/repos/a/b/actions/runs")).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(api.rate_limit().remaining, Some(4999));
+        assert_eq!(api.rate_limit().limit, Some(5000));
+    }
+    
+    #[tokio::test]
+    async fn github_fetch_backs_off_when_rate_limited() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "60"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = GitHubApi::new(Client::new(), &server.uri(), "t");
+        assert!(api.fetch(api.get("/repos/a/b/actions/runs")).await.is_err());
+        assert!(api.rate_limit().is_blocked(Utc::now()));
+        
+        let error = api.fetch(api.get("/repos/a/b/actions/runs")).await.unwrap_err();
+        assert!(error.to_string().starts_with("GitHub rate limit reached"));
+        
+        let error = api.send(api.post("/repos/a/b/actions/runs/1/rerun")).await.unwrap_err();
+        assert!(error.to_string().starts_with("GitHub rate limit reached"));
+    }
+    
+    #[tokio::test]
+    async fn github_send_records_quota() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/a/b/actions/runs/1/rerun"))
+            .respond_with(ResponseTemplate::new(201).insert_header("x-ratelimit-remaining", "4000"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = GitHubApi::new(Client::new(), &server.uri(), "t");
+        let clone = api.clone();
+        api.send(api.post("/repos/a/b/actions/runs/1/rerun")).await.unwrap();
+        assert_eq!(clone.rate_limit().remaining, Some(4000));
+    }
+    
+    #[test]
+    fn response_cache_drops_least_recently_requested() {
+        let mut cache = ResponseCache::default();
+        for i in 0..RESPONSE_CACHE_LIMIT {
+            let key = format!("/runs/{}", i);
+            cache.get(&key);
+            cache.insert(key, "etag".to_string(), String::new());
+        }
+        assert!(cache.get("/runs/0").is_some());
+        
+        cache.get("/runs/new");
+        cache.insert("/runs/new".to_string(), "etag".to_string(), String::new());
+        assert_eq!(cache.entries.len(), RESPONSE_CACHE_LIMIT);
+        assert!(cache.entries.contains_key("/runs/0"));
+        assert!(!cache.entries.contains_key("/runs/1"));
+    }
+    
+    #[test]
+    fn exhausted_quota_blocks_until_reset() {
+        let now = Utc::now();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", (now.timestamp() + 300).to_string().parse().unwrap());
+        
+        let mut limit = RateLimit::default();
+        limit.update(StatusCode::FORBIDDEN, &headers, now);
+        assert!(limit.is_blocked(now));
+        assert!(!limit.is_blocked(now + Duration::seconds(301)));
+    }
+    
+    #[test]
+    fn jenkins_folders_map_to_nested_job_paths() {
+        let api = JenkinsApi::new(Client::new(), "https://ci.example.com/", "team/app", None, None);
+        assert_eq!(api.url("/api/json"), "https://ci.example.com/job/team/job/app/api/json");
+    }
+}
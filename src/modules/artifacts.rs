@@ -0,0 +1,284 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use super::api::{GitHubApi, GitLabApi};
+use super::coverage::{CoverageInfo, CoverageModule, ReportFormat};
+use super::test_results::{self, TestCase};
+
+/// A downloadable build output. On GitLab every job has at most one
+/// artifacts archive, so `id` is the job id there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactInfo {
+    pub id: u64,
+    pub name: String,
+    pub size: u64,
+    pub expired: bool,
+}
+
+/// Something found inside an artifact that the dashboard can show.
+#[derive(Debug)]
+pub enum ArtifactReport {
+    Coverage(CoverageInfo),
+    Tests(Vec<TestCase>),
+}
+
+#[derive(Deserialize)]
+struct GitHubArtifactsResponse {
+    artifacts: Vec<GitHubArtifact>,
+}
+
+#[derive(Deserialize)]
+struct GitHubArtifact {
+    id: u64,
+    name: String,
+    size_in_bytes: u64,
+    #[serde(default)]
+    expired: bool,
+}
+
+#[derive(Deserialize)]
+struct GitLabJob {
+    name: String,
+    artifacts_file: Option<GitLabArtifactsFile>,
+    artifacts_expire_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Deserialize)]
+struct GitLabArtifactsFile {
+    size: u64,
+}
+
+pub struct ArtifactsModule;
+
+impl ArtifactsModule {
+    pub async fn get_github_artifacts(api: &GitHubApi, repo: &str, run_id: u64) -> Result<Vec<ArtifactInfo>> {
+        let body = api
+            .fetch(api.get(&format!("/repos/{}/actions/runs/{}/artifacts", repo, run_id)))
+            .await?;
+        
+        let data: GitHubArtifactsResponse = serde_json::from_str(&body)?;
+        Ok(data
+            .artifacts
+            .into_iter()
+            .map(|artifact| ArtifactInfo {
+                id: artifact.id,
+                name: artifact.name,
+                size: artifact.size_in_bytes,
+                expired: artifact.expired,
+            })
+            .collect())
+    }
+    
+    /// GitHub answers with a redirect to short-lived blob storage, which
+    /// reqwest follows without forwarding the token.
+    pub async fn download_github_artifact(api: &GitHubApi, repo: &str, artifact_id: u64) -> Result<Vec<u8>> {
+        let response = api
+            .send(api.get(&format!("/repos/{}/actions/artifacts/{}/zip", repo, artifact_id)))
+            .await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+    
+    pub async fn get_gitlab_artifacts(api: &GitLabApi, project_id: &str, job_id: u64) -> Result<Vec<ArtifactInfo>> {
+        let response = api
+            .get(&format!("/projects/{}/jobs/{}", project_id, job_id))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        let job: GitLabJob = response.json().await?;
+        let expired = job.artifacts_expire_at.is_some_and(|at| at < chrono::Utc::now());
+        Ok(job
+            .artifacts_file
+            .map(|file| ArtifactInfo {
+                id: job_id,
+                name: job.name,
+                size: file.size,
+                expired,
+            })
+            .into_iter()
+            .collect())
+    }
+    
+    pub async fn download_gitlab_artifacts(api: &GitLabApi, project_id: &str, job_id: u64) -> Result<Vec<u8>> {
+        let response = api
+            .get(&format!("/projects/{}/jobs/{}/artifacts", project_id, job_id))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// Folder under `root` an artifact is unpacked into. The name comes from
+/// the server (a GitLab job name may contain `/`), so anything that could
+/// form a path is replaced.
+pub fn target_dir(root: &Path, artifact: &ArtifactInfo) -> PathBuf {
+    let name: String = artifact
+        .name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || "-_. ".contains(c) { c } else { '_' })
+        .collect();
+    root.join(format!("{}-{}", artifact.id, name.replace("..", "_")))
+}
+
+/// Unpacks a zip archive into `dir` and returns the files written. Entries
+/// that would land outside `dir` are skipped.
+pub fn extract(archive: &[u8], dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive))?;
+    let mut files = Vec::new();
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index)?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let path = dir.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(&path)?;
+        std::io::copy(&mut entry, &mut file)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+/// Recognises the coverage formats `ReportFormat` knows and JUnit XML by
+/// extension and content. Anything else is ignored.
+pub fn read_report(path: &Path) -> Option<ArtifactReport> {
+    let name = path.file_name()?.to_str()?;
+    let extensions = [".info", ".lcov", ".xml", ".json"];
+    if name != "lcov" && !extensions.iter().any(|extension| name.ends_with(extension)) {
+        return None;
+    }
+    let content = fs::read_to_string(path).ok()?;
+    
+    if let Some(format) = ReportFormat::detect(path, &content) {
+        if let Ok(coverage) = CoverageModule::parse(format, &content) {
+            return Some(ArtifactReport::Coverage(coverage));
+        }
+    }
+    if !name.ends_with(".xml") {
+        return None;
+    }
+    test_results::parse_junit(&content).ok().map(ArtifactReport::Tests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use zip::write::SimpleFileOptions;
+    
+    const LCOV: &str = include_str!("../../tests/fixtures/coverage/lcov.info");
+    const JUNIT: &str = include_str!("../../tests/fixtures/tests/junit.xml");
+    
+    fn archive(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+    
+    #[test]
+    fn extracted_reports_are_recognised() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let zip = archive(&[
+            ("coverage/lcov.info", LCOV),
+            ("junit.xml", JUNIT),
+            ("README.md", "not a report"),
+            ("../escape.info", LCOV),
+        ]);
+        
+        let files = extract(&zip, dir.path()).unwrap();
+        assert_eq!(files.len(), 3);
+        assert!(!dir.path().parent().unwrap().join("escape.info").exists());
+        
+        let reports: Vec<ArtifactReport> = files.iter().filter_map(|file| read_report(file)).collect();
+        assert_eq!(reports.len(), 2);
+        assert!(matches!(&reports[0], ArtifactReport::Coverage(c) if c.total_coverage() == 70.0));
+        assert!(matches!(&reports[1], ArtifactReport::Tests(cases) if cases.len() == 4));
+    }
+    
+    #[test]
+    fn target_dir_stays_inside_the_artifact_dir() {
+        let root = Path::new("artifacts");
+        let artifact = |name: &str| ArtifactInfo { id: 7, name: name.to_string(), size: 0, expired: false };
+        
+        assert_eq!(target_dir(root, &artifact("coverage report")), root.join("7-coverage report"));
+        for name in ["../../etc", "test: linux/x86", "..\\up", ".."] {
+            let dir = target_dir(root, &artifact(name));
+            assert_eq!(dir.parent(), Some(root), "{}", name);
+            assert!(!dir.file_name().unwrap().to_str().unwrap().contains(".."), "{}", name);
+        }
+    }
+    
+    #[tokio::test]
+    async fn github_artifacts_are_listed_and_downloaded() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/app/actions/runs/11/artifacts"))
+            .and(header("Authorization", "Bearer gh-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"total_count":2,"artifacts":[
+                    {"id":501,"name":"coverage","size_in_bytes":2048,"expired":false},
+                    {"id":502,"name":"binaries","size_in_bytes":9000000,"expired":true}
+                ]}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/app/actions/artifacts/501/zip"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(archive(&[("lcov.info", LCOV)])))
+            .mount(&server)
+            .await;
+        
+        let api = GitHubApi::new(reqwest::Client::new(), &server.uri(), "gh-token");
+        let artifacts = ArtifactsModule::get_github_artifacts(&api, "acme/app", 11).await.unwrap();
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(artifacts[0].name, "coverage");
+        assert!(artifacts[1].expired);
+        
+        let zip = ArtifactsModule::download_github_artifact(&api, "acme/app", 501).await.unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(extract(&zip, dir.path()).unwrap().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn gitlab_job_archive_is_listed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/projects/42/jobs/7001"))
+            .and(header("PRIVATE-TOKEN", "gl-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"id":7001,"name":"test","artifacts_file":{"filename":"artifacts.zip","size":1234},
+                    "artifacts_expire_at":"2099-01-01T00:00:00Z"}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/projects/42/jobs/7002"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"id":7002,"name":"lint"}"#))
+            .mount(&server)
+            .await;
+        
+        let api = GitLabApi::new(reqwest::Client::new(), &server.uri(), "gl-token");
+        let artifacts = ArtifactsModule::get_gitlab_artifacts(&api, "42", 7001).await.unwrap();
+        assert_eq!(
+            artifacts,
+            vec![ArtifactInfo { id: 7001, name: "test".to_string(), size: 1234, expired: false }]
+        );
+        assert!(ArtifactsModule::get_gitlab_artifacts(&api, "42", 7002).await.unwrap().is_empty());
+    }
+}
@@ -1,29 +1,223 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::api::{GitHubApi, GitLabApi};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum BuildStatus {
     Success,
     Failure,
     Running,
+    /// Waiting for a runner, a resource or an earlier stage.
+    Queued,
+    Cancelled,
+    Skipped,
+    TimedOut,
+    /// Needs approval or other input before it can continue.
+    ActionRequired,
+    /// A GitLab job that only runs when started by hand.
+    Manual,
     Unknown,
 }
 
+impl BuildStatus {
+    /// Still queued or running, so worth polling again.
+    pub fn is_active(self) -> bool {
+        matches!(self, BuildStatus::Running | BuildStatus::Queued)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BuildInfo {
+    pub id: u64,
     pub status: BuildStatus,
     pub name: String,
-    pub timestamp: String,
+    /// Empty when the provider does not say which branch was built.
+    pub branch: String,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Only set once the run has completed.
+    pub finished_at: Option<DateTime<Utc>>,
     pub commit_sha: String,
     pub message: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunFilter {
+    MyBranch,
+    DefaultBranch,
+    All,
+}
+
+impl RunFilter {
+    pub fn next(self) -> Self {
+        match self {
+            RunFilter::MyBranch => RunFilter::DefaultBranch,
+            RunFilter::DefaultBranch => RunFilter::All,
+            RunFilter::All => RunFilter::MyBranch,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            RunFilter::MyBranch => "my branch",
+            RunFilter::DefaultBranch => "default branch",
+            RunFilter::All => "all",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum BuildAction {
+    Rerun(u64),
+    RerunFailed(u64),
+    Cancel(u64),
+    Dispatch {
+        workflow: String,
+        git_ref: String,
+        inputs: HashMap<String, String>,
+    },
+}
+
+impl BuildAction {
+    pub fn describe(&self) -> String {
+        match self {
+            BuildAction::Rerun(id) => format!("Re-run all jobs of run #{}", id),
+            BuildAction::RerunFailed(id) => format!("Re-run failed jobs of run #{}", id),
+            BuildAction::Cancel(id) => format!("Cancel run #{}", id),
+            BuildAction::Dispatch { workflow, git_ref, inputs } => {
+                let mut inputs: Vec<String> = inputs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                inputs.sort();
+                format!("Dispatch {} on {} {}", workflow, git_ref, inputs.join(" "))
+            }
+        }
+    }
+    
+    /// Status to show for the affected run until the next refresh confirms it.
+    pub fn expected_status(&self) -> Option<BuildStatus> {
+        match self {
+            BuildAction::Rerun(_) | BuildAction::RerunFailed(_) => Some(BuildStatus::Queued),
+            BuildAction::Cancel(_) => Some(BuildStatus::Cancelled),
+            BuildAction::Dispatch { .. } => None,
+        }
+    }
+    
+    pub fn run_id(&self) -> Option<u64> {
+        match self {
+            BuildAction::Rerun(id) | BuildAction::RerunFailed(id) | BuildAction::Cancel(id) => Some(*id),
+            BuildAction::Dispatch { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    pub id: u64,
+    pub name: String,
+    pub status: BuildStatus,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub steps: Vec<StepInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StepInfo {
+    pub name: String,
+    pub status: BuildStatus,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Wall-clock time between start and finish. Still-running entries are
+/// measured against now; entries that never started have no duration.
+pub fn duration(
+    started_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+) -> Option<chrono::Duration> {
+    let start = started_at?;
+    let end = completed_at.unwrap_or_else(Utc::now);
+    Some(end - start)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowStats {
+    pub name: String,
+    pub runs: usize,
+    /// Share of finished runs that succeeded; cancelled and skipped runs
+    /// are left out. `None` until a run has finished.
+    pub success_rate: Option<f64>,
+    pub average_duration: Option<chrono::Duration>,
+    /// Both passed and failed on the same commit, so the failures are
+    /// probably not caused by the code.
+    pub flaky: bool,
+}
+
+/// Per-workflow figures over the fetched runs, in order of first
+/// appearance (the most recently run workflow first).
+pub fn workflow_stats(builds: &[BuildInfo]) -> Vec<WorkflowStats> {
+    let mut names: Vec<&str> = Vec::new();
+    for build in builds {
+        if !names.contains(&build.name.as_str()) {
+            names.push(&build.name);
+        }
+    }
+    
+    names
+        .into_iter()
+        .map(|name| {
+            let runs: Vec<&BuildInfo> = builds.iter().filter(|b| b.name == name).collect();
+            let failed = |b: &BuildInfo| matches!(b.status, BuildStatus::Failure | BuildStatus::TimedOut);
+            let passed = |b: &BuildInfo| b.status == BuildStatus::Success;
+            
+            let finished = runs.iter().filter(|b| failed(b) || passed(b)).count();
+            let successes = runs.iter().filter(|b| passed(b)).count();
+            let durations: Vec<chrono::Duration> = runs
+                .iter()
+                .filter(|b| b.finished_at.is_some())
+                .filter_map(|b| duration(b.started_at, b.finished_at))
+                .collect();
+            let flaky = runs
+                .iter()
+                .filter(|f| failed(f))
+                .any(|f| runs.iter().any(|p| passed(p) && p.commit_sha == f.commit_sha));
+            
+            WorkflowStats {
+                name: name.to_string(),
+                runs: runs.len(),
+                success_rate: (finished > 0).then(|| successes as f64 / finished as f64 * 100.0),
+                average_duration: (!durations.is_empty())
+                    .then(|| durations.iter().fold(chrono::Duration::zero(), |a, d| a + *d) / durations.len() as i32),
+                flaky,
+            }
+        })
+        .collect()
+}
+
+/// Coarse "how long ago" text for run lists, e.g. `5m ago` or `3d ago`.
+pub fn relative_time(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - at;
+    if elapsed.num_seconds() < 60 {
+        "just now".to_string()
+    } else if elapsed.num_minutes() < 60 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_hours() < 24 {
+        format!("{}h ago", elapsed.num_hours())
+    } else {
+        format!("{}d ago", elapsed.num_days())
+    }
+}
+
 #[derive(Deserialize)]
 struct GitHubWorkflowRun {
+    id: u64,
     status: String,
     conclusion: Option<String>,
     name: String,
-    updated_at: String,
+    #[serde(default)]
+    head_branch: Option<String>,
+    run_started_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
     head_sha: String,
     head_commit: Option<GitHubCommit>,
 }
@@ -38,38 +232,117 @@ struct GitHubResponse {
     workflow_runs: Vec<GitHubWorkflowRun>,
 }
 
+#[derive(Deserialize)]
+struct GitHubJob {
+    id: u64,
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    started_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    steps: Vec<GitHubStep>,
+}
+
+#[derive(Deserialize)]
+struct GitHubStep {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    started_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+/// Also returned by Gitea's GitHub-compatible Actions API.
+#[derive(Deserialize)]
+pub(super) struct GitHubJobsResponse {
+    jobs: Vec<GitHubJob>,
+}
+
+impl GitHubJobsResponse {
+    pub(super) fn into_jobs(self) -> Vec<JobInfo> {
+        self.jobs
+            .into_iter()
+            .map(|job| JobInfo {
+                id: job.id,
+                name: job.name,
+                status: github_status(&job.status, job.conclusion.as_deref()),
+                started_at: job.started_at,
+                completed_at: job.completed_at,
+                steps: job
+                    .steps
+                    .into_iter()
+                    .map(|step| StepInfo {
+                        name: step.name,
+                        status: github_status(&step.status, step.conclusion.as_deref()),
+                        started_at: step.started_at,
+                        completed_at: step.completed_at,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+pub(super) fn gitlab_status(status: &str) -> BuildStatus {
+    match status {
+        "success" => BuildStatus::Success,
+        "failed" => BuildStatus::Failure,
+        "running" => BuildStatus::Running,
+        "created" | "waiting_for_resource" | "preparing" | "pending" | "scheduled" => BuildStatus::Queued,
+        "canceled" | "canceling" => BuildStatus::Cancelled,
+        "skipped" => BuildStatus::Skipped,
+        "manual" => BuildStatus::Manual,
+        _ => BuildStatus::Unknown,
+    }
+}
+
+pub(super) fn parse_time(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+    value
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+pub(super) fn github_status(status: &str, conclusion: Option<&str>) -> BuildStatus {
+    match (status, conclusion) {
+        ("completed", Some("success")) => BuildStatus::Success,
+        ("completed", Some("failure")) | ("completed", Some("startup_failure")) => BuildStatus::Failure,
+        ("completed", Some("cancelled")) => BuildStatus::Cancelled,
+        ("completed", Some("skipped")) | ("completed", Some("neutral")) => BuildStatus::Skipped,
+        ("completed", Some("timed_out")) => BuildStatus::TimedOut,
+        ("completed", Some("action_required")) => BuildStatus::ActionRequired,
+        ("in_progress", _) => BuildStatus::Running,
+        ("queued", _) | ("waiting", _) | ("pending", _) | ("requested", _) => BuildStatus::Queued,
+        _ => BuildStatus::Unknown,
+    }
+}
+
 pub struct BuildModule;
 
 impl BuildModule {
     pub async fn get_github_status(
+        api: &GitHubApi,
         repo: &str,
-        token: &str,
+        branch: Option<&str>,
     ) -> Result<Vec<BuildInfo>> {
-        let url = format!("https://api.github.com/repos/{}/actions/runs?per_page=10", repo);
-        let client = reqwest::Client::new();
-        
-        let response = client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Accept", "application/vnd.github+json")
-            .send()
+        let body = api
+            .fetch(api.get(&format!("/repos/{}/actions/runs?per_page=10", repo)).query(&[("branch", branch)]))
             .await?;
         
-        let data: GitHubResponse = response.json().await?;
+        let data: GitHubResponse = serde_json::from_str(&body)?;
         
         let mut builds = Vec::new();
         for run in data.workflow_runs {
-            let status = match (&run.status[..], run.conclusion.as_deref()) {
-                ("completed", Some("success")) => BuildStatus::Success,
-                ("completed", Some("failure")) => BuildStatus::Failure,
-                ("in_progress", _) | ("queued", _) => BuildStatus::Running,
-                _ => BuildStatus::Unknown,
-            };
+            let status = github_status(&run.status, run.conclusion.as_deref());
             
             builds.push(BuildInfo {
+                id: run.id,
                 status,
                 name: run.name,
-                timestamp: run.updated_at,
+                branch: run.head_branch.unwrap_or_default(),
+                started_at: run.run_started_at,
+                finished_at: run.updated_at.filter(|_| run.status == "completed"),
                 commit_sha: run.head_sha.chars().take(7).collect(),
                 message: run.head_commit
                     .map(|c| c.message.lines().next().unwrap_or("").to_string())
@@ -80,35 +353,75 @@ impl BuildModule {
         Ok(builds)
     }
     
+    pub async fn get_github_jobs(
+        api: &GitHubApi,
+        repo: &str,
+        run_id: u64,
+    ) -> Result<Vec<JobInfo>> {
+        let body = api
+            .fetch(api.get(&format!("/repos/{}/actions/runs/{}/jobs", repo, run_id)))
+            .await?;
+        
+        let data: GitHubJobsResponse = serde_json::from_str(&body)?;
+        Ok(data.into_jobs())
+    }
+    
+    pub async fn run_github_action(api: &GitHubApi, repo: &str, action: &BuildAction) -> Result<()> {
+        let base = format!("/repos/{}/actions", repo);
+        let (path, body) = match action {
+            BuildAction::Rerun(id) => (format!("{}/runs/{}/rerun", base, id), None),
+            BuildAction::RerunFailed(id) => (format!("{}/runs/{}/rerun-failed-jobs", base, id), None),
+            BuildAction::Cancel(id) => (format!("{}/runs/{}/cancel", base, id), None),
+            BuildAction::Dispatch { workflow, git_ref, inputs } => (
+                format!("{}/workflows/{}/dispatches", base, workflow),
+                Some(serde_json::json!({ "ref": git_ref, "inputs": inputs })),
+            ),
+        };
+        
+        let mut request = api.post(&path);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        
+        api.send(request).await?;
+        Ok(())
+    }
+    
+    pub async fn get_github_job_log(api: &GitHubApi, repo: &str, job_id: u64) -> Result<String> {
+        let response = api
+            .send(api.get(&format!("/repos/{}/actions/jobs/{}/logs", repo, job_id)))
+            .await?;
+        
+        Ok(response.text().await?)
+    }
+    
     pub async fn get_gitlab_status(
+        api: &GitLabApi,
         project_id: &str,
-        token: &str,
+        branch: Option<&str>,
     ) -> Result<Vec<BuildInfo>> {
-        let url = format!("https://gitlab.com/api/v4/projects/{}/pipelines?per_page=10", project_id);
-        let client = reqwest::Client::new();
-        
-        let response = client
-            .get(&url)
-            .header("PRIVATE-TOKEN", token)
+        let response = api
+            .get(&format!("/projects/{}/pipelines?per_page=10", project_id))
+            .query(&[("ref", branch)])
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
         
         let pipelines: Vec<serde_json::Value> = response.json().await?;
         
         let mut builds = Vec::new();
         for pipeline in pipelines {
-            let status_str = pipeline["status"].as_str().unwrap_or("unknown");
-            let status = match status_str {
-                "success" => BuildStatus::Success,
-                "failed" => BuildStatus::Failure,
-                "running" => BuildStatus::Running,
-                _ => BuildStatus::Unknown,
-            };
+            let status = gitlab_status(pipeline["status"].as_str().unwrap_or("unknown"));
             
+            // The list endpoint has no start and finish times; creation and
+            // last update are the closest approximation.
             builds.push(BuildInfo {
+                id: pipeline["id"].as_u64().unwrap_or(0),
                 status,
                 name: "Pipeline".to_string(),
-                timestamp: pipeline["updated_at"].as_str().unwrap_or("").to_string(),
+                branch: pipeline["ref"].as_str().unwrap_or("").to_string(),
+                started_at: parse_time(&pipeline["created_at"]),
+                finished_at: parse_time(&pipeline["updated_at"]).filter(|_| !status.is_active()),
                 commit_sha: pipeline["sha"]
                     .as_str()
                     .map(|s| s.chars().take(7).collect())
@@ -119,4 +432,392 @@ impl BuildModule {
         
         Ok(builds)
     }
+    
+    pub async fn get_gitlab_jobs(
+        api: &GitLabApi,
+        project_id: &str,
+        pipeline_id: u64,
+    ) -> Result<Vec<JobInfo>> {
+        let response = api
+            .get(&format!("/projects/{}/pipelines/{}/jobs?per_page=100", project_id, pipeline_id))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        let jobs: Vec<serde_json::Value> = response.json().await?;
+        
+        Ok(jobs
+            .iter()
+            .map(|job| JobInfo {
+                id: job["id"].as_u64().unwrap_or(0),
+                name: format!(
+                    "{} / {}",
+                    job["stage"].as_str().unwrap_or(""),
+                    job["name"].as_str().unwrap_or("job")
+                ),
+                status: match job["failure_reason"].as_str() {
+                    Some("job_execution_timeout") => BuildStatus::TimedOut,
+                    _ => gitlab_status(job["status"].as_str().unwrap_or("unknown")),
+                },
+                started_at: parse_time(&job["started_at"]),
+                completed_at: parse_time(&job["finished_at"]),
+                steps: Vec::new(),
+            })
+            .collect())
+    }
+    
+    pub async fn run_gitlab_action(api: &GitLabApi, project_id: &str, action: &BuildAction) -> Result<()> {
+        let base = format!("/projects/{}", project_id);
+        let (path, body) = match action {
+            BuildAction::Rerun(id) | BuildAction::RerunFailed(id) => (format!("{}/pipelines/{}/retry", base, id), None),
+            BuildAction::Cancel(id) => (format!("{}/pipelines/{}/cancel", base, id), None),
+            BuildAction::Dispatch { git_ref, inputs, .. } => {
+                let variables: Vec<serde_json::Value> = inputs
+                    .iter()
+                    .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+                    .collect();
+                (
+                    format!("{}/pipeline", base),
+                    Some(serde_json::json!({ "ref": git_ref, "variables": variables })),
+                )
+            }
+        };
+        
+        let mut request = api.post(&path);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+    
+    pub async fn get_gitlab_job_log(api: &GitLabApi, project_id: &str, job_id: u64) -> Result<String> {
+        let response = api
+            .get(&format!("/projects/{}/jobs/{}/trace", project_id, job_id))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        Ok(response.text().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    async fn github(server: &MockServer) -> GitHubApi {
+        GitHubApi::new(reqwest::Client::new(), &format!("{}/api/v3", server.uri()), "gh-token")
+    }
+    
+    async fn gitlab(server: &MockServer) -> GitLabApi {
+        GitLabApi::new(reqwest::Client::new(), &format!("{}/api/v4", server.uri()), "gl-token")
+    }
+    
+    #[tokio::test]
+    async fn github_runs_use_enterprise_base_url_and_branch_filter() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/repos/acme/app/actions/runs"))
+            .and(query_param("branch", "feature/x"))
+            .and(header("Authorization", "Bearer gh-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "workflow_runs": [
+                    {
+                        "id": 11,
+                        "status": "completed",
+                        "conclusion": "failure",
+                        "name": "CI",
+                        "run_started_at": "2024-05-01T11:52:30Z",
+                        "updated_at": "2024-05-01T12:00:00Z",
+                        "head_sha": "abcdef1234567890",
+                        "head_commit": { "message": "Fix parser\n\nDetails" }
+                    },
+                    {
+                        "id": 12,
+                        "status": "in_progress",
+                        "conclusion": null,
+                        "name": "Lint",
+                        "updated_at": "2024-05-01T12:05:00Z",
+                        "head_sha": "1234567abcdef",
+                        "head_commit": null
+                    }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let builds = BuildModule::get_github_status(&github(&server).await, "acme/app", Some("feature/x"))
+            .await
+            .unwrap();
+        
+        assert_eq!(builds.len(), 2);
+        assert_eq!(builds[0].id, 11);
+        assert!(matches!(builds[0].status, BuildStatus::Failure));
+        assert_eq!(builds[0].commit_sha, "abcdef1");
+        assert_eq!(builds[0].message, "Fix parser");
+        assert_eq!(
+            duration(builds[0].started_at, builds[0].finished_at),
+            Some(chrono::Duration::seconds(450))
+        );
+        assert!(matches!(builds[1].status, BuildStatus::Running));
+        assert!(builds[1].finished_at.is_none());
+    }
+    
+    #[test]
+    fn github_conclusions_keep_their_own_states() {
+        assert_eq!(github_status("completed", Some("cancelled")), BuildStatus::Cancelled);
+        assert_eq!(github_status("completed", Some("skipped")), BuildStatus::Skipped);
+        assert_eq!(github_status("completed", Some("timed_out")), BuildStatus::TimedOut);
+        assert_eq!(github_status("completed", Some("action_required")), BuildStatus::ActionRequired);
+        assert_eq!(github_status("queued", None), BuildStatus::Queued);
+        assert_eq!(gitlab_status("manual"), BuildStatus::Manual);
+        assert_eq!(gitlab_status("canceled"), BuildStatus::Cancelled);
+        assert_eq!(gitlab_status("pending"), BuildStatus::Queued);
+    }
+    
+    #[test]
+    fn workflow_stats_and_flaky_detection() {
+        let run = |id: u64, name: &str, status: BuildStatus, sha: &str, minutes: i64| {
+            let start = Utc::now() - chrono::Duration::hours(id as i64);
+            BuildInfo {
+                id,
+                status,
+                name: name.to_string(),
+                branch: "main".to_string(),
+                started_at: Some(start),
+                finished_at: (!status.is_active()).then(|| start + chrono::Duration::minutes(minutes)),
+                commit_sha: sha.to_string(),
+                message: String::new(),
+            }
+        };
+        let builds = vec![
+            run(1, "CI", BuildStatus::Running, "ccc", 0),
+            run(2, "CI", BuildStatus::Success, "bbb", 4),
+            run(3, "Lint", BuildStatus::Success, "bbb", 1),
+            run(4, "CI", BuildStatus::Failure, "bbb", 6),
+            run(5, "CI", BuildStatus::Cancelled, "aaa", 2),
+            run(6, "CI", BuildStatus::Success, "aaa", 5),
+            run(7, "Lint", BuildStatus::Failure, "aaa", 1),
+        ];
+        
+        let stats = workflow_stats(&builds);
+        assert_eq!(stats.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["CI", "Lint"]);
+        
+        let ci = &stats[0];
+        assert_eq!(ci.runs, 5);
+        assert_eq!(ci.success_rate.map(|r| r.round()), Some(67.0));
+        assert_eq!(ci.average_duration, Some(chrono::Duration::seconds(255)));
+        assert!(ci.flaky);
+        
+        assert_eq!(stats[1].success_rate, Some(50.0));
+        assert!(!stats[1].flaky);
+    }
+    
+    #[test]
+    fn relative_times() {
+        let now = Utc::now();
+        assert_eq!(relative_time(now - chrono::Duration::seconds(20), now), "just now");
+        assert_eq!(relative_time(now - chrono::Duration::minutes(5), now), "5m ago");
+        assert_eq!(relative_time(now - chrono::Duration::hours(26), now), "1d ago");
+    }
+    
+    #[tokio::test]
+    async fn github_jobs_include_steps_with_durations() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/repos/acme/app/actions/runs/11/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jobs": [{
+                    "id": 99,
+                    "name": "test",
+                    "status": "completed",
+                    "conclusion": "failure",
+                    "started_at": "2024-05-01T12:00:00Z",
+                    "completed_at": "2024-05-01T12:03:30Z",
+                    "steps": [
+                        {
+                            "name": "cargo test",
+                            "status": "completed",
+                            "conclusion": "failure",
+                            "started_at": "2024-05-01T12:01:00Z",
+                            "completed_at": "2024-05-01T12:03:00Z"
+                        }
+                    ]
+                }]
+            })))
+            .mount(&server)
+            .await;
+        
+        let jobs = BuildModule::get_github_jobs(&github(&server).await, "acme/app", 11)
+            .await
+            .unwrap();
+        
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, 99);
+        assert_eq!(
+            duration(jobs[0].started_at, jobs[0].completed_at),
+            Some(chrono::Duration::seconds(210))
+        );
+        assert_eq!(jobs[0].steps[0].name, "cargo test");
+        assert!(matches!(jobs[0].steps[0].status, BuildStatus::Failure));
+    }
+    
+    #[tokio::test]
+    async fn github_actions_post_to_run_and_workflow_endpoints() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/repos/acme/app/actions/runs/11/rerun-failed-jobs"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/repos/acme/app/actions/workflows/deploy.yml/dispatches"))
+            .and(body_json(json!({ "ref": "main", "inputs": { "env": "staging" } })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = github(&server).await;
+        BuildModule::run_github_action(&api, "acme/app", &BuildAction::RerunFailed(11))
+            .await
+            .unwrap();
+        let dispatch = BuildAction::Dispatch {
+            workflow: "deploy.yml".to_string(),
+            git_ref: "main".to_string(),
+            inputs: HashMap::from([("env".to_string(), "staging".to_string())]),
+        };
+        BuildModule::run_github_action(&api, "acme/app", &dispatch).await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn github_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "message": "Bad credentials" })))
+            .mount(&server)
+            .await;
+        
+        let result = BuildModule::get_github_status(&github(&server).await, "acme/app", None).await;
+        assert!(result.is_err());
+    }
+    
+    #[tokio::test]
+    async fn github_job_log_is_returned_as_text() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/repos/acme/app/actions/jobs/99/logs"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("line 1\nerror: boom\n"))
+            .mount(&server)
+            .await;
+        
+        let log = BuildModule::get_github_job_log(&github(&server).await, "acme/app", 99)
+            .await
+            .unwrap();
+        assert_eq!(log, "line 1\nerror: boom\n");
+    }
+    
+    #[tokio::test]
+    async fn gitlab_pipelines_use_self_hosted_url_and_private_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/pipelines"))
+            .and(query_param("ref", "main"))
+            .and(header("PRIVATE-TOKEN", "gl-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "id": 7, "status": "success", "sha": "feedface00", "updated_at": "2024-05-01T12:00:00Z" },
+                { "id": 6, "status": "failed", "sha": "deadbeef00", "updated_at": "2024-05-01T11:00:00Z" }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let builds = BuildModule::get_gitlab_status(&gitlab(&server).await, "42", Some("main"))
+            .await
+            .unwrap();
+        
+        assert_eq!(builds.len(), 2);
+        assert_eq!(builds[0].id, 7);
+        assert!(matches!(builds[0].status, BuildStatus::Success));
+        assert_eq!(builds[1].commit_sha, "deadbee");
+        assert!(matches!(builds[1].status, BuildStatus::Failure));
+    }
+    
+    #[tokio::test]
+    async fn gitlab_jobs_and_trace() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/pipelines/7/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": 501,
+                "name": "rspec",
+                "stage": "test",
+                "status": "failed",
+                "started_at": "2024-05-01T12:00:00.000Z",
+                "finished_at": "2024-05-01T12:01:00.000Z"
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/jobs/501/trace"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("FAILED tests\n"))
+            .mount(&server)
+            .await;
+        
+        let api = gitlab(&server).await;
+        let jobs = BuildModule::get_gitlab_jobs(&api, "42", 7).await.unwrap();
+        assert_eq!(jobs[0].name, "test / rspec");
+        assert!(matches!(jobs[0].status, BuildStatus::Failure));
+        assert_eq!(
+            duration(jobs[0].started_at, jobs[0].completed_at),
+            Some(chrono::Duration::seconds(60))
+        );
+        
+        let log = BuildModule::get_gitlab_job_log(&api, "42", 501).await.unwrap();
+        assert_eq!(log, "FAILED tests\n");
+    }
+    
+    #[tokio::test]
+    async fn gitlab_retry_cancel_and_trigger() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v4/projects/42/pipelines/7/retry"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v4/projects/42/pipelines/7/cancel"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v4/projects/42/pipeline"))
+            .and(body_json(json!({
+                "ref": "main",
+                "variables": [{ "key": "DEPLOY", "value": "1" }]
+            })))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = gitlab(&server).await;
+        BuildModule::run_gitlab_action(&api, "42", &BuildAction::Rerun(7)).await.unwrap();
+        BuildModule::run_gitlab_action(&api, "42", &BuildAction::Cancel(7)).await.unwrap();
+        let trigger = BuildAction::Dispatch {
+            workflow: "pipeline".to_string(),
+            git_ref: "main".to_string(),
+            inputs: HashMap::from([("DEPLOY".to_string(), "1".to_string())]),
+        };
+        BuildModule::run_gitlab_action(&api, "42", &trigger).await.unwrap();
+    }
 }
@@ -0,0 +1,353 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use super::build::BuildStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CargoCommand {
+    Build,
+    Test,
+    Clippy,
+}
+
+impl CargoCommand {
+    pub fn next(self) -> Self {
+        match self {
+            CargoCommand::Build => CargoCommand::Test,
+            CargoCommand::Test => CargoCommand::Clippy,
+            CargoCommand::Clippy => CargoCommand::Build,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            CargoCommand::Build => "cargo build",
+            CargoCommand::Test => "cargo test",
+            CargoCommand::Clippy => "cargo clippy",
+        }
+    }
+    
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            CargoCommand::Build => &["build", "--all-targets"],
+            CargoCommand::Test => &["test"],
+            CargoCommand::Clippy => &["clippy", "--all-targets"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticLevel {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Diagnostic {
+    pub fn location(&self) -> String {
+        match &self.file {
+            Some(file) => format!("{}:{}:{}", file, self.line, self.column),
+            None => String::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CargoEvent {
+    Artifact,
+    Diagnostic(Diagnostic),
+    Finished { success: bool },
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<CompilerMessage>,
+}
+
+#[derive(Deserialize)]
+struct CompilerMessage {
+    level: String,
+    message: String,
+    #[serde(default)]
+    spans: Vec<CompilerSpan>,
+}
+
+#[derive(Deserialize)]
+struct CompilerSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+}
+
+/// Parses one line of `--message-format=json` output. Anything that is not
+/// a compiler artifact, an error or a warning (notes, summaries, test
+/// harness output) is skipped.
+pub fn parse_line(line: &str) -> Option<CargoEvent> {
+    let message: CargoMessage = serde_json::from_str(line).ok()?;
+    match message.reason.as_str() {
+        "compiler-artifact" => Some(CargoEvent::Artifact),
+        "compiler-message" => {
+            let message = message.message?;
+            let level = match message.level.as_str() {
+                "error" | "error: internal compiler error" => DiagnosticLevel::Error,
+                "warning" => DiagnosticLevel::Warning,
+                _ => return None,
+            };
+            // Summaries like "3 warnings emitted" have no spans.
+            let span = message.spans.iter().find(|span| span.is_primary);
+            if span.is_none() && message.message.ends_with("emitted") {
+                return None;
+            }
+            Some(CargoEvent::Diagnostic(Diagnostic {
+                level,
+                message: message.message,
+                file: span.map(|span| span.file_name.clone()),
+                line: span.map_or(0, |span| span.line_start),
+                column: span.map_or(0, |span| span.column_start),
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// A cargo command running in the background. Output is parsed on a tokio
+/// task and handed over through a channel, so the UI only has to `poll`.
+pub struct LocalBuild {
+    pub command: CargoCommand,
+    pub diagnostics: Vec<Diagnostic>,
+    pub artifacts: usize,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub success: Option<bool>,
+    events: UnboundedReceiver<CargoEvent>,
+    task: JoinHandle<()>,
+}
+
+impl LocalBuild {
+    pub fn spawn(command: CargoCommand, repo_path: &str) -> Self {
+        let (sender, events) = mpsc::unbounded_channel();
+        let repo_path = repo_path.to_string();
+        let task = tokio::spawn(async move {
+            let success = Self::run(command, &repo_path, &sender).await.unwrap_or_else(|e| {
+                let _ = sender.send(CargoEvent::Diagnostic(Diagnostic {
+                    level: DiagnosticLevel::Error,
+                    message: format!("failed to run {}: {}", command.label(), e),
+                    file: None,
+                    line: 0,
+                    column: 0,
+                }));
+                false
+            });
+            let _ = sender.send(CargoEvent::Finished { success });
+        });
+        
+        LocalBuild {
+            command,
+            diagnostics: Vec::new(),
+            artifacts: 0,
+            started_at: Utc::now(),
+            finished_at: None,
+            success: None,
+            events,
+            task,
+        }
+    }
+    
+    /// Fatal cargo errors (no manifest, failed resolution or build script)
+    /// only reach stderr, so its last lines become an error diagnostic when
+    /// the command fails without reporting one as JSON.
+    async fn run(command: CargoCommand, repo_path: &str, sender: &UnboundedSender<CargoEvent>) -> std::io::Result<bool> {
+        let mut child = Command::new("cargo")
+            .args(command.args())
+            .arg("--message-format=json")
+            .current_dir(repo_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        
+        // Read concurrently so a chatty stderr cannot fill its pipe and stall
+        // cargo while stdout is being read.
+        let stderr = child.stderr.take();
+        let stderr_tail = tokio::spawn(async move {
+            let mut tail = VecDeque::new();
+            if let Some(stderr) = stderr {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if tail.len() == 20 {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+            }
+            tail
+        });
+        
+        let mut reported_error = false;
+        if let Some(stdout) = child.stdout.take() {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Some(line) = lines.next_line().await? {
+                if let Some(event) = parse_line(&line) {
+                    if let CargoEvent::Diagnostic(Diagnostic { level: DiagnosticLevel::Error, .. }) = &event {
+                        reported_error = true;
+                    }
+                    let _ = sender.send(event);
+                }
+            }
+        }
+        
+        let success = child.wait().await?.success();
+        let tail = stderr_tail.await.unwrap_or_default();
+        if !success && !reported_error && !tail.is_empty() {
+            let _ = sender.send(CargoEvent::Diagnostic(Diagnostic {
+                level: DiagnosticLevel::Error,
+                message: Vec::from(tail).join("\n"),
+                file: None,
+                line: 0,
+                column: 0,
+            }));
+        }
+        Ok(success)
+    }
+    
+    /// Applies the events received so far. Returns true once, when the
+    /// command has just finished.
+    pub fn poll(&mut self) -> bool {
+        let mut finished = false;
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                CargoEvent::Artifact => self.artifacts += 1,
+                // The same diagnostic is reported once per target that
+                // includes the file (lib and tests, for example).
+                CargoEvent::Diagnostic(diagnostic) => {
+                    if !self.diagnostics.contains(&diagnostic) {
+                        self.diagnostics.push(diagnostic);
+                    }
+                }
+                CargoEvent::Finished { success } => {
+                    self.success = Some(success);
+                    self.finished_at = Some(Utc::now());
+                    finished = true;
+                }
+            }
+        }
+        finished
+    }
+    
+    /// Dropping the task drops the child process, which kills it.
+    pub fn cancel(&mut self) {
+        self.task.abort();
+        self.success = Some(false);
+        self.finished_at = Some(Utc::now());
+    }
+    
+    pub fn is_running(&self) -> bool {
+        self.success.is_none()
+    }
+    
+    pub fn status(&self) -> BuildStatus {
+        match self.success {
+            None => BuildStatus::Running,
+            Some(true) => BuildStatus::Success,
+            Some(false) => BuildStatus::Failure,
+        }
+    }
+    
+    pub fn count(&self, level: DiagnosticLevel) -> usize {
+        self.diagnostics.iter().filter(|d| d.level == level).count()
+    }
+    
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {} errors, {} warnings",
+            self.command.label(),
+            self.count(DiagnosticLevel::Error),
+            self.count(DiagnosticLevel::Warning)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const CLIPPY: &str = include_str!("../../tests/fixtures/cargo/clippy.jsonl");
+    
+    #[test]
+    fn clippy_fixture_yields_errors_warnings_and_artifacts() {
+        let events: Vec<CargoEvent> = CLIPPY.lines().filter_map(parse_line).collect();
+        
+        let artifacts = events.iter().filter(|e| matches!(e, CargoEvent::Artifact)).count();
+        assert_eq!(artifacts, 2);
+        
+        let diagnostics: Vec<&Diagnostic> = events
+            .iter()
+            .filter_map(|e| match e {
+                CargoEvent::Diagnostic(d) => Some(d),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(diagnostics.len(), 3);
+        
+        assert_eq!(diagnostics[0].level, DiagnosticLevel::Warning);
+        assert_eq!(diagnostics[0].message, "unused variable: `count`");
+        assert_eq!(diagnostics[0].location(), "src/app.rs:42:13");
+        
+        assert_eq!(diagnostics[2].level, DiagnosticLevel::Error);
+        assert_eq!(diagnostics[2].message, "mismatched types");
+        assert_eq!(diagnostics[2].location(), "src/main.rs:88:20");
+    }
+    
+    #[tokio::test]
+    async fn spawn_failure_is_reported_as_error() {
+        let mut local = LocalBuild::spawn(CargoCommand::Build, "/nonexistent/devdash");
+        while !local.poll() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        
+        assert!(matches!(local.status(), BuildStatus::Failure));
+        assert_eq!(local.count(DiagnosticLevel::Error), 1);
+        assert!(local.diagnostics[0].message.starts_with("failed to run cargo build"));
+    }
+    
+    #[tokio::test]
+    async fn fatal_cargo_error_is_kept_from_stderr() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut local = LocalBuild::spawn(CargoCommand::Build, dir.path().to_str().unwrap());
+        while !local.poll() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        
+        assert!(matches!(local.status(), BuildStatus::Failure));
+        assert_eq!(local.count(DiagnosticLevel::Error), 1);
+        assert!(local.diagnostics[0].message.contains("Cargo.toml"));
+    }
+    
+    #[test]
+    fn non_json_and_summary_lines_are_skipped() {
+        assert!(parse_line("running 3 tests").is_none());
+        assert!(parse_line("test tests::it_works ... ok").is_none());
+        assert!(parse_line(r#"{"reason":"build-finished","success":true}"#).is_none());
+        assert!(parse_line(
+            r#"{"reason":"compiler-message","message":{"level":"warning","message":"2 warnings emitted","spans":[]}}"#
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+
+use super::api::{GitHubApi, GitLabApi, GiteaApi, JenkinsApi, RateLimit, WoodpeckerApi};
+use super::artifacts::{ArtifactInfo, ArtifactsModule};
+use super::build::{BuildAction, BuildInfo, BuildModule, BuildStatus, JobInfo};
+use super::gitea::GiteaModule;
+use super::jenkins::JenkinsModule;
+use super::woodpecker::WoodpeckerModule;
+
+/// The CI system behind the Build tab. Every provider reports runs as
+/// `BuildInfo` and their jobs as `JobInfo`; `run_id` and `job_id` are
+/// whatever the provider uses to address them (run id, pipeline number,
+/// build number).
+pub enum CiProvider {
+    GitHub { api: GitHubApi, repo: String },
+    GitLab { api: GitLabApi, project_id: String },
+    Gitea { api: GiteaApi, repo: String, tasks_only: OnceLock<bool> },
+    Jenkins { api: JenkinsApi, job: String },
+    Woodpecker { api: WoodpeckerApi, repo: String, repo_id: OnceLock<u64> },
+}
+
+impl CiProvider {
+    /// A dedicated CI server takes precedence over the forge hosting the
+    /// code, since teams that configure one usually build there instead.
+    /// GitHub reuses the app's client so pull requests and CI polling share
+    /// one cache and one quota.
+    pub fn from_config(config: &Config, client: &Client, github: Option<&GitHubApi>) -> Option<Self> {
+        if let Some(jenkins) = &config.jenkins {
+            return Some(CiProvider::Jenkins {
+                api: JenkinsApi::new(
+                    client.clone(),
+                    &jenkins.url,
+                    &jenkins.job,
+                    jenkins.user.as_deref(),
+                    jenkins.token.as_deref(),
+                ),
+                job: jenkins.job.rsplit('/').next().unwrap_or(&jenkins.job).to_string(),
+            });
+        }
+        if let Some(woodpecker) = &config.woodpecker {
+            return Some(CiProvider::Woodpecker {
+                api: WoodpeckerApi::new(client.clone(), &woodpecker.url, &woodpecker.token),
+                repo: woodpecker.repo.clone(),
+                repo_id: OnceLock::new(),
+            });
+        }
+        if let Some(gitea) = &config.gitea {
+            if let Some(repo) = &gitea.repo {
+                return Some(CiProvider::Gitea {
+                    api: GiteaApi::new(client.clone(), &gitea.url, gitea.token.as_deref()),
+                    repo: repo.clone(),
+                    tasks_only: OnceLock::new(),
+                });
+            }
+        }
+        if let (Some(api), Some(repo)) = (github, &config.github_repo) {
+            return Some(CiProvider::GitHub {
+                api: api.clone(),
+                repo: repo.clone(),
+            });
+        }
+        if let (Some(token), Some(project_id)) = (&config.gitlab_token, &config.gitlab_project_id) {
+            return Some(CiProvider::GitLab {
+                api: GitLabApi::new(client.clone(), &config.gitlab_api_url, token),
+                project_id: project_id.clone(),
+            });
+        }
+        None
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            CiProvider::GitHub { .. } => "GitHub Actions",
+            CiProvider::GitLab { .. } => "GitLab CI",
+            CiProvider::Gitea { .. } => "Gitea Actions",
+            CiProvider::Jenkins { .. } => "Jenkins",
+            CiProvider::Woodpecker { .. } => "Woodpecker",
+        }
+    }
+    
+    /// Only GitHub reports a quota worth showing.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        match self {
+            CiProvider::GitHub { api, .. } => Some(api.rate_limit()),
+            _ => None,
+        }
+    }
+    
+    pub async fn runs(&self, branch: Option<&str>) -> Result<Vec<BuildInfo>> {
+        match self {
+            CiProvider::GitHub { api, repo } => BuildModule::get_github_status(api, repo, branch).await,
+            CiProvider::GitLab { api, project_id } => BuildModule::get_gitlab_status(api, project_id, branch).await,
+            CiProvider::Gitea { api, repo, tasks_only } => GiteaModule::get_status(api, repo, branch, tasks_only).await,
+            CiProvider::Jenkins { api, job } => JenkinsModule::get_status(api, job, branch).await,
+            CiProvider::Woodpecker { api, .. } => {
+                WoodpeckerModule::get_status(api, self.woodpecker_repo_id().await?, branch).await
+            }
+        }
+    }
+    
+    pub async fn jobs(&self, run_id: u64) -> Result<Vec<JobInfo>> {
+        match self {
+            CiProvider::GitHub { api, repo } => BuildModule::get_github_jobs(api, repo, run_id).await,
+            CiProvider::GitLab { api, project_id } => BuildModule::get_gitlab_jobs(api, project_id, run_id).await,
+            CiProvider::Gitea { api, repo, tasks_only } => GiteaModule::get_jobs(api, repo, run_id, tasks_only).await,
+            CiProvider::Jenkins { api, .. } => JenkinsModule::get_jobs(api, run_id).await,
+            CiProvider::Woodpecker { api, .. } => {
+                WoodpeckerModule::get_jobs(api, self.woodpecker_repo_id().await?, run_id).await
+            }
+        }
+    }
+    
+    pub async fn run_action(&self, action: &BuildAction) -> Result<()> {
+        match self {
+            CiProvider::GitHub { api, repo } => BuildModule::run_github_action(api, repo, action).await,
+            CiProvider::GitLab { api, project_id } => BuildModule::run_gitlab_action(api, project_id, action).await,
+            CiProvider::Gitea { api, repo, .. } => GiteaModule::run_action(api, repo, action).await,
+            CiProvider::Jenkins { api, .. } => JenkinsModule::run_action(api, action).await,
+            CiProvider::Woodpecker { api, .. } => {
+                WoodpeckerModule::run_action(api, self.woodpecker_repo_id().await?, action).await
+            }
+        }
+    }
+    
+    /// Status to show for the run an action targets until the next refresh.
+    /// Jenkins and Woodpecker rerun by starting a new build or pipeline with
+    /// its own number, so the old one keeps its status.
+    pub fn expected_status(&self, action: &BuildAction) -> Option<BuildStatus> {
+        match (self, action) {
+            (CiProvider::Jenkins { .. }, BuildAction::Rerun(_)) => None,
+            (CiProvider::Woodpecker { .. }, BuildAction::Rerun(_)) => None,
+            _ => action.expected_status(),
+        }
+    }
+    
+    pub async fn job_log(&self, run_id: u64, job_id: u64) -> Result<String> {
+        match self {
+            CiProvider::GitHub { api, repo } => BuildModule::get_github_job_log(api, repo, job_id).await,
+            CiProvider::GitLab { api, project_id } => BuildModule::get_gitlab_job_log(api, project_id, job_id).await,
+            CiProvider::Gitea { api, repo, .. } => GiteaModule::get_job_log(api, repo, job_id).await,
+            CiProvider::Jenkins { api, .. } => JenkinsModule::get_job_log(api, run_id).await,
+            CiProvider::Woodpecker { api, .. } => {
+                WoodpeckerModule::get_job_log(api, self.woodpecker_repo_id().await?, run_id, job_id).await
+            }
+        }
+    }
+    
+    /// GitHub keeps artifacts per run, GitLab per job; `job_id` is only
+    /// used for GitLab.
+    pub async fn artifacts(&self, run_id: u64, job_id: Option<u64>) -> Result<Vec<ArtifactInfo>> {
+        match (self, job_id) {
+            (CiProvider::GitHub { api, repo }, _) => ArtifactsModule::get_github_artifacts(api, repo, run_id).await,
+            (CiProvider::GitLab { api, project_id }, Some(job_id)) => {
+                ArtifactsModule::get_gitlab_artifacts(api, project_id, job_id).await
+            }
+            (CiProvider::GitLab { .. }, None) => Err(anyhow!("select a job to list its artifacts")),
+            _ => Err(anyhow!("artifacts are not supported for {}", self.name())),
+        }
+    }
+    
+    /// Returns the artifact as a zip archive.
+    pub async fn download_artifact(&self, artifact: &ArtifactInfo) -> Result<Vec<u8>> {
+        match self {
+            CiProvider::GitHub { api, repo } => ArtifactsModule::download_github_artifact(api, repo, artifact.id).await,
+            CiProvider::GitLab { api, project_id } => {
+                ArtifactsModule::download_gitlab_artifacts(api, project_id, artifact.id).await
+            }
+            _ => Err(anyhow!("artifacts are not supported for {}", self.name())),
+        }
+    }
+    
+    /// Looked up on first use and kept for the rest of the session.
+    async fn woodpecker_repo_id(&self) -> Result<u64> {
+        let CiProvider::Woodpecker { api, repo, repo_id } = self else {
+            unreachable!("only called for Woodpecker");
+        };
+        if let Some(id) = repo_id.get() {
+            return Ok(*id);
+        }
+        let id = WoodpeckerModule::lookup_repo(api, repo).await?;
+        Ok(*repo_id.get_or_init(|| id))
+    }
+}
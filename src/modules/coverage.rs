@@ -1,84 +1,1106 @@
-use anyhow::Result;
-use std::process::Command;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::SystemTime;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
 
-#[derive(Debug, Clone)]
+/// Where coverage tools write their reports by default, relative to the
+/// repository. `CoverageRun` writes the first one.
+pub const DEFAULT_REPORTS: &[&str] = &[
+    "target/llvm-cov/coverage.json",
+    "lcov.info",
+    "coverage/lcov.info",
+    "target/coverage/lcov.info",
+    "cobertura.xml",
+    "coverage.xml",
+    "tarpaulin-report.json",
+];
+
+/// How many items of one kind (lines, functions, ...) there are and how
+/// many of them ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Counts {
+    pub count: u64,
+    pub covered: u64,
+}
+
+impl Counts {
+    pub fn percent(&self) -> f64 {
+        percent(self.covered, self.count)
+    }
+    
+    pub fn uncovered(&self) -> u64 {
+        self.count.saturating_sub(self.covered)
+    }
+    
+    /// Compares the covered fraction exactly, without rounding through
+    /// percentages.
+    pub fn is_below(&self, other: &Counts) -> bool {
+        (self.covered as u128) * (other.count as u128) < (other.covered as u128) * (self.count as u128)
+    }
+    
+    fn add(&mut self, other: Counts) {
+        self.count += other.count;
+        self.covered += other.covered;
+    }
+}
+
+/// Totals for one file or the whole report. Kinds a format does not
+/// report stay at zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageSummary {
+    #[serde(default)]
+    pub lines: Counts,
+    #[serde(default)]
+    pub functions: Counts,
+    #[serde(default)]
+    pub regions: Counts,
+    #[serde(default)]
+    pub branches: Counts,
+}
+
+impl CoverageSummary {
+    fn add(&mut self, other: CoverageSummary) {
+        self.lines.add(other.lines);
+        self.functions.add(other.functions);
+        self.regions.add(other.regions);
+        self.branches.add(other.branches);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct CoverageInfo {
-    pub total_coverage: f64,
+    pub totals: CoverageSummary,
     pub files: Vec<FileCoverage>,
+    /// Set when the report came from somewhere other than a local run,
+    /// e.g. the name of a CI artifact.
+    pub source: Option<String>,
+}
+
+impl CoverageInfo {
+    /// Line coverage, the figure every format reports.
+    pub fn total_coverage(&self) -> f64 {
+        self.totals.lines.percent()
+    }
+    
+    /// Adds another report's files and totals, e.g. from a subproject.
+    pub fn merge(&mut self, other: CoverageInfo) {
+        self.totals.add(other.totals);
+        self.files.extend(other.files);
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct FileCoverage {
     pub name: String,
-    pub coverage: f64,
+    pub summary: CoverageSummary,
+    /// Hit count of every instrumented line, by line number. Lines that
+    /// are not in the map are not code, e.g. comments.
+    pub hits: BTreeMap<u32, u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CoverageSort {
+    #[default]
+    Path,
+    Lines,
+    Functions,
+    Branches,
+    Uncovered,
+}
+
+impl CoverageSort {
+    pub fn next(self) -> Self {
+        match self {
+            CoverageSort::Path => CoverageSort::Lines,
+            CoverageSort::Lines => CoverageSort::Functions,
+            CoverageSort::Functions => CoverageSort::Branches,
+            CoverageSort::Branches => CoverageSort::Uncovered,
+            CoverageSort::Uncovered => CoverageSort::Path,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            CoverageSort::Path => "path",
+            CoverageSort::Lines => "lines",
+            CoverageSort::Functions => "functions",
+            CoverageSort::Branches => "branches",
+            CoverageSort::Uncovered => "uncovered lines",
+        }
+    }
+    
+    /// Percentages sort worst first, with files the report has no data
+    /// for at the end; uncovered lines sort most first.
+    pub fn compare(self, a: &FileCoverage, b: &FileCoverage) -> Ordering {
+        let by_percent = |a: Counts, b: Counts| {
+            (a.count == 0)
+                .cmp(&(b.count == 0))
+                .then(a.percent().total_cmp(&b.percent()))
+        };
+        let ordering = match self {
+            CoverageSort::Path => Ordering::Equal,
+            CoverageSort::Lines => by_percent(a.summary.lines, b.summary.lines),
+            CoverageSort::Functions => by_percent(a.summary.functions, b.summary.functions),
+            CoverageSort::Branches => by_percent(a.summary.branches, b.summary.branches),
+            CoverageSort::Uncovered => b.summary.lines.uncovered().cmp(&a.summary.lines.uncovered()),
+        };
+        ordering.then_with(|| a.name.cmp(&b.name))
+    }
+}
+
+/// What per-file changes in the Coverage tab are measured against.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CoverageBaseline {
+    #[default]
+    Previous,
+    MergeBase,
+}
+
+impl CoverageBaseline {
+    pub fn next(self) -> Self {
+        match self {
+            CoverageBaseline::Previous => CoverageBaseline::MergeBase,
+            CoverageBaseline::MergeBase => CoverageBaseline::Previous,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            CoverageBaseline::Previous => "previous snapshot",
+            CoverageBaseline::MergeBase => "merge-base",
+        }
+    }
+}
+
+/// Which changes patch coverage is measured for.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PatchScope {
+    /// Uncommitted changes, staged or not.
+    #[default]
+    WorkingTree,
+    /// Everything since the branch forked from the default branch.
+    Branch,
+}
+
+impl PatchScope {
+    pub fn next(self) -> Self {
+        match self {
+            PatchScope::WorkingTree => PatchScope::Branch,
+            PatchScope::Branch => PatchScope::WorkingTree,
+        }
+    }
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            PatchScope::WorkingTree => "working tree",
+            PatchScope::Branch => "branch",
+        }
+    }
+}
+
+/// Coverage of only the changed lines. Changed lines that are not code,
+/// like comments, are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PatchCoverage {
+    pub lines: Counts,
+    /// Changed lines no test ran, as (path, line number).
+    pub uncovered: Vec<(String, u32)>,
+}
+
+impl PatchCoverage {
+    /// `changed` maps repository-relative paths to changed line numbers.
+    pub fn new(coverage: &CoverageInfo, changed: &BTreeMap<String, Vec<u32>>, root: &str) -> Self {
+        let mut patch = PatchCoverage::default();
+        for file in &coverage.files {
+            let name = relative_name(&file.name, root);
+            let Some(lines) = changed.get(name) else {
+                continue;
+            };
+            for line in lines {
+                match file.hits.get(line) {
+                    Some(0) => {
+                        patch.lines.count += 1;
+                        patch.uncovered.push((name.to_string(), *line));
+                    }
+                    Some(_) => {
+                        patch.lines.count += 1;
+                        patch.lines.covered += 1;
+                    }
+                    None => {}
+                }
+            }
+        }
+        patch.uncovered.sort();
+        patch
+    }
+}
+
+/// A row of the coverage table: a file, or in the directory rollup a
+/// directory with the totals of everything below it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageRow {
+    pub depth: usize,
+    pub name: String,
+    pub summary: CoverageSummary,
+    /// Index into `CoverageInfo::files`; `None` for directories.
+    pub file: Option<usize>,
+}
+
+/// Reports usually name files by absolute path; inside the repository the
+/// path relative to it is shown instead.
+pub fn relative_name<'a>(name: &'a str, root: &str) -> &'a str {
+    if root.is_empty() {
+        return name;
+    }
+    name.strip_prefix(root)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(name)
+}
+
+pub fn file_rows(files: &[FileCoverage], root: &str, sort: CoverageSort) -> Vec<CoverageRow> {
+    let mut order: Vec<usize> = (0..files.len()).collect();
+    order.sort_by(|&a, &b| sort.compare(&files[a], &files[b]));
+    order
+        .into_iter()
+        .map(|index| CoverageRow {
+            depth: 0,
+            name: relative_name(&files[index].name, root).to_string(),
+            summary: files[index].summary,
+            file: Some(index),
+        })
+        .collect()
+}
+
+/// Every directory followed by its contents, in path order.
+pub fn directory_tree(files: &[FileCoverage], root: &str) -> Vec<CoverageRow> {
+    let mut nodes: BTreeMap<Vec<&str>, (CoverageSummary, Option<usize>)> = BTreeMap::new();
+    for (index, file) in files.iter().enumerate() {
+        let parts: Vec<&str> = relative_name(&file.name, root)
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .collect();
+        for depth in 1..parts.len() {
+            nodes.entry(parts[..depth].to_vec()).or_default().0.add(file.summary);
+        }
+        nodes.insert(parts, (file.summary, Some(index)));
+    }
+    
+    nodes
+        .into_iter()
+        .map(|(parts, (summary, file))| CoverageRow {
+            depth: parts.len().saturating_sub(1),
+            name: parts.last().copied().unwrap_or_default().to_string(),
+            summary,
+            file,
+        })
+        .collect()
+}
+
+/// A source file with the hit count of each line, opened from the
+/// Coverage tab. `scroll` is the line at the top of the view.
+#[derive(Debug, Clone)]
+pub struct SourceView {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub hits: BTreeMap<u32, u64>,
+    pub scroll: usize,
+}
+
+impl SourceView {
+    /// Opens the file and scrolls to the first uncovered line, if any.
+    /// Paths that are not absolute are taken relative to the repository.
+    pub fn open(file: &FileCoverage, repo_path: &str, root: &str) -> Result<Self> {
+        let name = relative_name(&file.name, root);
+        let path = Path::new(repo_path).join(name);
+        let content = fs::read_to_string(&path).map_err(|e| anyhow!("Cannot open {}: {}", path.display(), e))?;
+        
+        let mut view = SourceView {
+            title: name.to_string(),
+            lines: content.lines().map(str::to_string).collect(),
+            hits: file.hits.clone(),
+            scroll: 0,
+        };
+        if let Some(&first) = view.uncovered_blocks().first() {
+            view.scroll = first;
+        }
+        Ok(view)
+    }
+    
+    /// Hit count of a line by its index in `lines`.
+    pub fn hits(&self, index: usize) -> Option<u64> {
+        self.hits.get(&(index as u32 + 1)).copied()
+    }
+    
+    /// Indices of the first line of every run of uncovered lines, so that
+    /// jumping skips over the rest of the run.
+    pub fn uncovered_blocks(&self) -> Vec<usize> {
+        self.hits
+            .iter()
+            .filter(|(&line, &count)| line > 0 && count == 0 && self.hits.get(&(line - 1)) != Some(&0))
+            .map(|(&line, _)| line as usize - 1)
+            .collect()
+    }
+    
+    pub fn uncovered_lines(&self) -> usize {
+        self.hits.values().filter(|&&count| count == 0).count()
+    }
+    
+    pub fn next_uncovered(&mut self) {
+        let blocks = self.uncovered_blocks();
+        if let Some(&line) = blocks.iter().find(|&&line| line > self.scroll).or(blocks.first()) {
+            self.scroll = line;
+        }
+    }
+    
+    pub fn prev_uncovered(&mut self) {
+        let blocks = self.uncovered_blocks();
+        if let Some(&line) = blocks.iter().rev().find(|&&line| line < self.scroll).or(blocks.last()) {
+            self.scroll = line;
+        }
+    }
+    
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll = (self.scroll + amount).min(self.lines.len().saturating_sub(1));
+    }
+    
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll = self.scroll.saturating_sub(amount);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    LlvmCov,
+    Lcov,
+    Cobertura,
+    Tarpaulin,
+}
+
+impl ReportFormat {
+    /// Guesses the format from the file extension, then checks the content
+    /// looks like it.
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name == "lcov" || name.ends_with(".info") || name.ends_with(".lcov") {
+            return content
+                .lines()
+                .any(|line| line.starts_with("SF:"))
+                .then_some(ReportFormat::Lcov);
+        }
+        if name.ends_with(".xml") {
+            return content.contains("<coverage").then_some(ReportFormat::Cobertura);
+        }
+        if name.ends_with(".json") {
+            if content.contains("llvm.coverage.json.export") {
+                return Some(ReportFormat::LlvmCov);
+            }
+            if content.contains("\"traces\"") {
+                return Some(ReportFormat::Tarpaulin);
+            }
+        }
+        None
+    }
+}
+
+/// `llvm-cov export` output, as printed by `cargo llvm-cov --json`.
+#[derive(Deserialize)]
+struct LlvmCovExport {
+    data: Vec<LlvmCovData>,
+}
+
+#[derive(Deserialize)]
+struct LlvmCovData {
+    files: Vec<LlvmCovFile>,
+    totals: CoverageSummary,
+}
+
+#[derive(Deserialize)]
+struct LlvmCovFile {
+    filename: String,
+    /// `[line, column, count, has_count, is_region_entry, is_gap_region]`;
+    /// older exports leave out the last field.
+    #[serde(default)]
+    segments: Vec<Vec<serde_json::Value>>,
+    summary: CoverageSummary,
+}
+
+/// `cargo tarpaulin --out Json` output. Paths come split into components.
+#[derive(Deserialize)]
+struct TarpaulinReport {
+    files: Vec<TarpaulinFile>,
+}
+
+#[derive(Deserialize)]
+struct TarpaulinFile {
+    path: Vec<String>,
+    #[serde(default)]
+    traces: Vec<TarpaulinTrace>,
+    covered: u64,
+    coverable: u64,
+}
+
+/// `stats` is `{"Line": hits}` for lines; branch and condition traces are
+/// not shown.
+#[derive(Deserialize)]
+struct TarpaulinTrace {
+    line: u32,
+    stats: serde_json::Value,
 }
 
 pub struct CoverageModule;
 
 impl CoverageModule {
-    pub fn get_coverage() -> Result<CoverageInfo> {
-        let output = Command::new("cargo")
-            .args(&["llvm-cov", "--json"])
-            .output()?;
-        
-        if !output.status.success() {
-            return Ok(CoverageInfo {
-                total_coverage: 0.0,
-                files: Vec::new(),
-            });
+    /// Reads several reports, e.g. one per subproject, merged into one.
+    pub fn load_reports(paths: &[PathBuf]) -> Result<CoverageInfo> {
+        let mut coverage: Option<CoverageInfo> = None;
+        for path in paths {
+            let info = Self::read_report(path)?;
+            match &mut coverage {
+                Some(coverage) => coverage.merge(info),
+                None => coverage = Some(info),
+            }
+        }
+        coverage.ok_or_else(|| anyhow!("no coverage report found"))
+    }
+    
+    pub fn read_report(path: &Path) -> Result<CoverageInfo> {
+        let content = fs::read_to_string(path)?;
+        let format = ReportFormat::detect(path, &content)
+            .ok_or_else(|| anyhow!("{} is not a known coverage report", path.display()))?;
+        Self::parse(format, &content)
+    }
+    
+    pub fn parse(format: ReportFormat, content: &str) -> Result<CoverageInfo> {
+        match format {
+            ReportFormat::LlvmCov => Self::parse_llvm_cov(content),
+            ReportFormat::Lcov => Ok(Self::parse_lcov(content)),
+            ReportFormat::Cobertura => Self::parse_cobertura(content),
+            ReportFormat::Tarpaulin => Self::parse_tarpaulin(content),
         }
-        
-        let json_str = String::from_utf8(output.stdout)?;
-        Self::parse_llvm_cov(&json_str)
     }
     
+    /// Uses the per-file and total `summary` objects llvm-cov computes
+    /// rather than re-deriving them from the raw segments.
     fn parse_llvm_cov(json_str: &str) -> Result<CoverageInfo> {
-        let json: serde_json::Value = serde_json::from_str(json_str)?;
+        let export: LlvmCovExport = serde_json::from_str(json_str)?;
+        
+        let mut totals = CoverageSummary::default();
+        let mut files = Vec::new();
+        for data in export.data {
+            totals.add(data.totals);
+            files.extend(data.files.into_iter().map(|file| FileCoverage {
+                hits: segment_hits(&file.segments),
+                name: file.filename,
+                summary: file.summary,
+            }));
+        }
         
-        let mut total_covered = 0;
-        let mut total_lines = 0;
+        Ok(CoverageInfo {
+            totals,
+            files,
+            source: None,
+        })
+    }
+    
+    /// Reads an lcov tracefile. `LF`/`LH` totals are used when present,
+    /// otherwise the `DA` lines of each record are counted.
+    pub fn parse_lcov(content: &str) -> CoverageInfo {
         let mut files = Vec::new();
+        let mut totals = CoverageSummary::default();
+        let mut name = String::new();
+        let mut summary = CoverageSummary::default();
+        let mut hits = BTreeMap::new();
+        let mut lines: Option<Counts> = None;
         
-        if let Some(data) = json["data"].as_array() {
-            for item in data {
-                if let Some(functions) = item["files"].as_array() {
-                    for func in functions {
-                        if let Some(segments) = func["segments"].as_array() {
-                            for segment in segments {
-                                if segment.get(0).and_then(|v| v.as_u64()).is_some() {
-                                    total_lines += 1;
-                                    if let Some(hit) = segment.get(1).and_then(|v| v.as_u64()) {
-                                        if hit > 0 {
-                                            total_covered += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        
-                        let filename = func["name"]
-                            .as_str()
-                            .unwrap_or("unknown")
-                            .to_string();
-                        
-                        files.push(FileCoverage {
-                            name: filename,
-                            coverage: 0.0,
-                        });
+        let number = |value: &str| value.trim().parse::<u64>().unwrap_or(0);
+        for line in content.lines() {
+            let line = line.trim();
+            let Some((key, value)) = line.split_once(':') else {
+                if line == "end_of_record" {
+                    summary.lines = lines.take().unwrap_or(summary.lines);
+                    totals.add(summary);
+                    files.push(FileCoverage {
+                        name: std::mem::take(&mut name),
+                        summary: std::mem::take(&mut summary),
+                        hits: std::mem::take(&mut hits),
+                    });
+                }
+                continue;
+            };
+            match key {
+                "SF" => name = value.to_string(),
+                "DA" => {
+                    let mut fields = value.split(',');
+                    let line = fields.next().map_or(0, number) as u32;
+                    let count = fields.next().map_or(0, number);
+                    summary.lines.count += 1;
+                    if count > 0 {
+                        summary.lines.covered += 1;
                     }
+                    *hits.entry(line).or_default() += count;
                 }
+                "LF" => lines.get_or_insert_with(Counts::default).count = number(value),
+                "LH" => lines.get_or_insert_with(Counts::default).covered = number(value),
+                "FNF" => summary.functions.count = number(value),
+                "FNH" => summary.functions.covered = number(value),
+                "BRF" => summary.branches.count = number(value),
+                "BRH" => summary.branches.covered = number(value),
+                _ => {}
             }
         }
         
-        let total_coverage = if total_lines > 0 {
-            (total_covered as f64 / total_lines as f64) * 100.0
-        } else {
-            0.0
+        CoverageInfo {
+            totals,
+            files,
+            source: None,
+        }
+    }
+    
+    /// Reads a Cobertura XML report. Classes are folded into their files,
+    /// since languages like Rust report one class per module.
+    pub fn parse_cobertura(content: &str) -> Result<CoverageInfo> {
+        // coverage.py and most other tools emit a DOCTYPE line.
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
         };
+        let document = roxmltree::Document::parse_with_options(content, options)?;
+        let root = document.root_element();
+        if !root.has_tag_name("coverage") {
+            return Err(anyhow!("not a Cobertura report"));
+        }
+        
+        let mut files: Vec<FileCoverage> = Vec::new();
+        for class in root.descendants().filter(|n| n.has_tag_name("class")) {
+            let name = class.attribute("filename").unwrap_or("unknown");
+            let mut summary = CoverageSummary::default();
+            let mut hits = BTreeMap::new();
+            for line in class.descendants().filter(|n| n.has_tag_name("line")) {
+                let count = line.attribute("hits").and_then(|h| h.parse::<u64>().ok()).unwrap_or(0);
+                summary.lines.count += 1;
+                if count > 0 {
+                    summary.lines.covered += 1;
+                }
+                if let Some(number) = line.attribute("number").and_then(|n| n.parse::<u32>().ok()) {
+                    hits.insert(number, count);
+                }
+                // e.g. condition-coverage="50% (1/2)"
+                let conditions = line
+                    .attribute("condition-coverage")
+                    .and_then(|c| c.split_once('('))
+                    .and_then(|(_, c)| c.trim_end_matches(')').split_once('/'));
+                if let Some((covered, count)) = conditions {
+                    summary.branches.add(Counts {
+                        count: count.parse().unwrap_or(0),
+                        covered: covered.parse().unwrap_or(0),
+                    });
+                }
+            }
+            match files.iter_mut().find(|file| file.name == name) {
+                Some(file) => {
+                    file.summary.lines.add(summary.lines);
+                    file.summary.branches.add(summary.branches);
+                    file.hits.extend(hits);
+                }
+                None => files.push(FileCoverage {
+                    name: name.to_string(),
+                    summary,
+                    hits,
+                }),
+            }
+        }
+        
+        let mut totals = CoverageSummary::default();
+        for file in &files {
+            totals.lines.add(file.summary.lines);
+            totals.branches.add(file.summary.branches);
+        }
+        let attribute = |name: &str| root.attribute(name).and_then(|v| v.parse::<u64>().ok());
+        if let (Some(count), Some(covered)) = (attribute("lines-valid"), attribute("lines-covered")) {
+            totals.lines = Counts { count, covered };
+        }
+        if let (Some(count), Some(covered)) = (attribute("branches-valid"), attribute("branches-covered")) {
+            totals.branches = Counts { count, covered };
+        }
+        
+        Ok(CoverageInfo {
+            totals,
+            files,
+            source: None,
+        })
+    }
+    
+    /// Tarpaulin only reports lines.
+    pub fn parse_tarpaulin(content: &str) -> Result<CoverageInfo> {
+        let report: TarpaulinReport = serde_json::from_str(content)?;
+        
+        let mut totals = CoverageSummary::default();
+        let files = report
+            .files
+            .into_iter()
+            .map(|file| {
+                let lines = Counts {
+                    count: file.coverable,
+                    covered: file.covered,
+                };
+                totals.lines.add(lines);
+                let hits = file
+                    .traces
+                    .iter()
+                    .filter_map(|trace| Some((trace.line, trace.stats.get("Line")?.as_u64()?)))
+                    .collect();
+                FileCoverage {
+                    name: file.path.iter().collect::<PathBuf>().display().to_string(),
+                    summary: CoverageSummary {
+                        lines,
+                        ..Default::default()
+                    },
+                    hits,
+                }
+            })
+            .collect();
         
         Ok(CoverageInfo {
-            total_coverage,
-            files: files.into_iter().take(10).collect(),
+            totals,
+            files,
+            source: None,
         })
     }
 }
+
+/// Works out per-line hit counts from llvm-cov's segments the way
+/// `llvm-cov show` does: a line counts if a region starts on it or a region
+/// from an earlier line is still open, and shows the highest count among
+/// them.
+fn segment_hits(segments: &[Vec<serde_json::Value>]) -> BTreeMap<u32, u64> {
+    struct Segment {
+        line: u32,
+        count: u64,
+        has_count: bool,
+        starts_region: bool,
+    }
+    let segments: Vec<Segment> = segments
+        .iter()
+        .filter_map(|segment| {
+            let flag = |index: usize| segment.get(index).and_then(|value| value.as_bool()).unwrap_or(false);
+            let has_count = flag(3);
+            Some(Segment {
+                line: segment.first()?.as_u64()? as u32,
+                count: segment.get(2)?.as_u64()?,
+                has_count,
+                starts_region: has_count && flag(4) && !flag(5),
+            })
+        })
+        .collect();
+    
+    let mut hits = BTreeMap::new();
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return hits;
+    };
+    let mut wrapped: Option<&Segment> = None;
+    let mut next = 0;
+    for line in first.line..=last.line {
+        let start = next;
+        while next < segments.len() && segments[next].line == line {
+            next += 1;
+        }
+        let on_line = &segments[start..next];
+        
+        let mut count = wrapped.filter(|segment| segment.has_count).map(|segment| segment.count);
+        for segment in on_line.iter().filter(|segment| segment.starts_region) {
+            count = Some(count.map_or(segment.count, |count| count.max(segment.count)));
+        }
+        if let Some(count) = count {
+            hits.insert(line, count);
+        }
+        if let Some(segment) = on_line.last() {
+            wrapped = Some(segment);
+        }
+    }
+    hits
+}
+
+/// The configured reports that exist, or else the most recently written of
+/// the default locations.
+fn report_files(repo_path: &str, reports: &[String]) -> Vec<(PathBuf, SystemTime)> {
+    let modified = |report: &str| {
+        let path = Path::new(repo_path).join(report);
+        let time = fs::metadata(&path).and_then(|metadata| metadata.modified()).ok()?;
+        Some((path, time))
+    };
+    if !reports.is_empty() {
+        return reports.iter().filter_map(|report| modified(report)).collect();
+    }
+    DEFAULT_REPORTS
+        .iter()
+        .filter_map(|report| modified(report))
+        .max_by_key(|(_, time)| *time)
+        .into_iter()
+        .collect()
+}
+
+/// Reloads coverage when report files change on disk. Comparing
+/// modification times is cheap enough to do on every refresh.
+#[derive(Debug, Default)]
+pub struct ReportWatcher {
+    seen: Vec<(PathBuf, SystemTime)>,
+}
+
+impl ReportWatcher {
+    /// Returns fresh coverage when a report was written, added or removed
+    /// since the last call. Nothing is returned while there is no report,
+    /// so the last coverage stays on screen.
+    pub fn poll(&mut self, repo_path: &str, reports: &[String]) -> Option<Result<CoverageInfo>> {
+        let files = report_files(repo_path, reports);
+        if files == self.seen {
+            return None;
+        }
+        self.seen = files;
+        if self.seen.is_empty() {
+            return None;
+        }
+        let paths: Vec<PathBuf> = self.seen.iter().map(|(path, _)| path.clone()).collect();
+        Some(CoverageModule::load_reports(&paths))
+    }
+}
+
+enum RunEvent {
+    Progress(String),
+    Finished(Result<CoverageInfo>),
+}
+
+/// `cargo llvm-cov` running in the background. It rebuilds and runs the
+/// whole test suite with instrumentation, so it only runs on request.
+pub struct CoverageRun {
+    pub started_at: DateTime<Utc>,
+    /// The last line cargo printed, e.g. the crate being compiled.
+    pub progress: String,
+    events: UnboundedReceiver<RunEvent>,
+    task: JoinHandle<()>,
+}
+
+impl CoverageRun {
+    pub fn spawn(repo_path: &str) -> Self {
+        let (sender, events) = mpsc::unbounded_channel();
+        let repo_path = repo_path.to_string();
+        let task = tokio::spawn(async move {
+            let result = Self::run(&repo_path, &sender).await;
+            let _ = sender.send(RunEvent::Finished(result));
+        });
+        
+        CoverageRun {
+            started_at: Utc::now(),
+            progress: String::new(),
+            events,
+            task,
+        }
+    }
+    
+    async fn run(repo_path: &str, sender: &UnboundedSender<RunEvent>) -> Result<CoverageInfo> {
+        let mut child = Command::new("cargo")
+            .args(["llvm-cov", "--json", "--output-path", DEFAULT_REPORTS[0]])
+            .current_dir(repo_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("failed to run cargo llvm-cov: {}", e))?;
+        
+        // Created only once the child started, i.e. the repository exists;
+        // the report is written long after, when the tests have run.
+        let output = Path::new(repo_path).join(DEFAULT_REPORTS[0]);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let mut last_line = String::new();
+        if let Some(stderr) = child.stderr.take() {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Some(line) = lines.next_line().await? {
+                let line = line.trim();
+                if !line.is_empty() {
+                    last_line = line.to_string();
+                    let _ = sender.send(RunEvent::Progress(last_line.clone()));
+                }
+            }
+        }
+        
+        if !child.wait().await?.success() {
+            return Err(anyhow!("cargo llvm-cov failed: {}", last_line));
+        }
+        CoverageModule::read_report(&output)
+    }
+    
+    /// Applies the progress received so far. Returns the result once, when
+    /// the run has finished.
+    pub fn poll(&mut self) -> Option<Result<CoverageInfo>> {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                RunEvent::Progress(line) => self.progress = line,
+                RunEvent::Finished(result) => return Some(result),
+            }
+        }
+        None
+    }
+    
+    /// Dropping the task drops the child process, which kills it.
+    pub fn cancel(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn percent(hit: u64, found: u64) -> f64 {
+    if found > 0 {
+        hit as f64 / found as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const LLVM_COV: &str = include_str!("../../tests/fixtures/coverage/llvm-cov.json");
+    const LCOV: &str = include_str!("../../tests/fixtures/coverage/lcov.info");
+    const COBERTURA: &str = include_str!("../../tests/fixtures/coverage/cobertura.xml");
+    const TARPAULIN: &str = include_str!("../../tests/fixtures/coverage/tarpaulin-report.json");
+    
+    #[test]
+    fn llvm_cov_fixture_uses_summaries() {
+        let coverage = CoverageModule::parse_llvm_cov(LLVM_COV).unwrap();
+        
+        assert_eq!(coverage.files.len(), 2);
+        assert_eq!(coverage.files[0].name, "/home/dev/devdash/src/modules/timer.rs");
+        assert_eq!(coverage.files[0].summary.lines, Counts { count: 40, covered: 30 });
+        assert_eq!(coverage.files[0].summary.functions, Counts { count: 6, covered: 5 });
+        assert_eq!(coverage.files[0].summary.regions, Counts { count: 52, covered: 41 });
+        assert_eq!(coverage.files[1].summary.lines.percent(), 50.0);
+        assert_eq!(coverage.files[1].summary.branches, Counts { count: 4, covered: 1 });
+        
+        assert_eq!(coverage.totals.lines, Counts { count: 50, covered: 35 });
+        assert_eq!(coverage.total_coverage(), 70.0);
+        assert_eq!(coverage.totals.functions, Counts { count: 8, covered: 6 });
+    }
+    
+    #[test]
+    fn lcov_fixture() {
+        let coverage = CoverageModule::parse_lcov(LCOV);
+        
+        assert_eq!(coverage.files.len(), 2);
+        assert_eq!(coverage.files[0].name, "src/modules/git.rs");
+        assert_eq!(coverage.files[0].summary.lines.percent(), 75.0);
+        assert_eq!(coverage.files[0].summary.functions, Counts { count: 1, covered: 1 });
+        // The second record has no LF/LH totals, so its DA lines are counted.
+        assert_eq!(coverage.files[1].summary.lines.percent(), 50.0);
+        assert_eq!(coverage.total_coverage(), 70.0);
+    }
+    
+    #[test]
+    fn cobertura_fixture() {
+        let coverage = CoverageModule::parse_cobertura(COBERTURA).unwrap();
+        
+        assert_eq!(coverage.files.len(), 2);
+        assert_eq!(coverage.files[0].name, "src/app.rs");
+        assert_eq!(coverage.files[0].summary.lines.percent(), 60.0);
+        assert_eq!(coverage.files[0].summary.branches, Counts { count: 2, covered: 1 });
+        assert_eq!(coverage.files[1].summary.lines.percent(), 100.0);
+        assert_eq!(coverage.total_coverage(), 75.0);
+        
+        assert!(CoverageModule::parse_cobertura("<testsuites/>").is_err());
+    }
+    
+    #[test]
+    fn tarpaulin_fixture() {
+        let coverage = CoverageModule::parse_tarpaulin(TARPAULIN).unwrap();
+        
+        assert_eq!(coverage.files.len(), 2);
+        assert_eq!(coverage.files[0].name, "/home/dev/devdash/src/modules/storage.rs");
+        assert_eq!(coverage.files[0].summary.lines.percent(), 50.0);
+        assert_eq!(coverage.total_coverage(), 60.0);
+    }
+    
+    #[test]
+    fn configured_reports_are_detected_and_merged() {
+        let reports = [
+            "tests/fixtures/coverage/lcov.info".to_string(),
+            "tests/fixtures/coverage/missing.info".to_string(),
+            "tests/fixtures/coverage/tarpaulin-report.json".to_string(),
+        ];
+        let mut watcher = ReportWatcher::default();
+        let coverage = watcher.poll(".", &reports).unwrap().unwrap();
+        
+        assert_eq!(coverage.files.len(), 4);
+        assert_eq!(coverage.totals.lines, Counts { count: 15, covered: 10 });
+        assert!(watcher.poll(".", &reports).is_none());
+        
+        let dir = Path::new("tests/fixtures/coverage");
+        assert_eq!(ReportFormat::detect(&dir.join("llvm-cov.json"), LLVM_COV), Some(ReportFormat::LlvmCov));
+        assert_eq!(ReportFormat::detect(&dir.join("cobertura.xml"), COBERTURA), Some(ReportFormat::Cobertura));
+        assert_eq!(ReportFormat::detect(&dir.join("junit.xml"), "<testsuites/>"), None);
+    }
+    
+    #[test]
+    fn newest_default_report_is_loaded_and_watched() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = dir.path().to_str().unwrap();
+        let mut watcher = ReportWatcher::default();
+        assert!(watcher.poll(repo, &[]).is_none());
+        
+        let write = |name: &str, content: &str, age: u64| {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            let time = SystemTime::now() - std::time::Duration::from_secs(age);
+            fs::File::options().write(true).open(&path).unwrap().set_modified(time).unwrap();
+        };
+        write("lcov.info", LCOV, 60);
+        write("tarpaulin-report.json", TARPAULIN, 120);
+        assert_eq!(watcher.poll(repo, &[]).unwrap().unwrap().total_coverage(), 70.0);
+        assert!(watcher.poll(repo, &[]).is_none());
+        
+        write(DEFAULT_REPORTS[0], LLVM_COV, 0);
+        let coverage = watcher.poll(repo, &[]).unwrap().unwrap();
+        assert_eq!(coverage.totals.regions, Counts { count: 66, covered: 47 });
+    }
+    
+    #[test]
+    fn line_hits_from_every_format() {
+        let llvm_cov = CoverageModule::parse_llvm_cov(LLVM_COV).unwrap();
+        let hits: Vec<(u32, u64)> = llvm_cov.files[0].hits.iter().map(|(&line, &count)| (line, count)).collect();
+        // Line 14 ends the region that started on line 12, so it still
+        // counts; lines 15 to 19 are outside any region.
+        assert_eq!(hits, [(12, 4), (13, 4), (14, 4), (20, 0)]);
+        
+        let lcov = CoverageModule::parse_lcov(LCOV);
+        assert_eq!(lcov.files[0].hits.get(&12), Some(&4));
+        assert_eq!(lcov.files[0].hits.get(&14), Some(&0));
+        
+        let cobertura = CoverageModule::parse_cobertura(COBERTURA).unwrap();
+        assert_eq!(cobertura.files[0].hits.len(), 5);
+        assert_eq!(cobertura.files[0].hits.get(&42), Some(&0));
+        
+        let tarpaulin = CoverageModule::parse_tarpaulin(TARPAULIN).unwrap();
+        assert_eq!(tarpaulin.files[0].hits.get(&10), Some(&3));
+        assert_eq!(tarpaulin.files[0].hits.get(&15), Some(&0));
+    }
+    
+    #[test]
+    fn source_view_jumps_between_uncovered_blocks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let source: String = (1..=10).map(|n| format!("line {}\n", n)).collect();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), source).unwrap();
+        let file = FileCoverage {
+            name: "src/lib.rs".to_string(),
+            summary: CoverageSummary::default(),
+            hits: [(1, 2), (2, 0), (3, 0), (5, 1), (7, 0), (9, 0)].into_iter().collect(),
+        };
+        
+        let mut view = SourceView::open(&file, dir.path().to_str().unwrap(), "").unwrap();
+        assert_eq!(view.lines.len(), 10);
+        assert_eq!(view.uncovered_blocks(), [1, 6, 8]);
+        assert_eq!(view.uncovered_lines(), 4);
+        assert_eq!(view.scroll, 1);
+        assert_eq!(view.hits(0), Some(2));
+        assert_eq!(view.hits(3), None);
+        
+        view.next_uncovered();
+        assert_eq!(view.scroll, 6);
+        view.next_uncovered();
+        view.next_uncovered();
+        assert_eq!(view.scroll, 1);
+        view.prev_uncovered();
+        assert_eq!(view.scroll, 8);
+        
+        assert!(SourceView::open(&file, "/nonexistent/devdash", "").is_err());
+    }
+    
+    #[test]
+    fn patch_coverage_counts_only_changed_code() {
+        let mut coverage = CoverageModule::parse_lcov(LCOV);
+        coverage.files[0].name = "/repo/src/modules/git.rs".to_string();
+        let changed: BTreeMap<String, Vec<u32>> = [
+            // Line 1 is not instrumented, 14 and 17 were never run.
+            ("src/modules/git.rs".to_string(), vec![1, 12, 13, 14, 17]),
+            ("README.md".to_string(), vec![3]),
+        ]
+        .into_iter()
+        .collect();
+        
+        let patch = PatchCoverage::new(&coverage, &changed, "/repo");
+        assert_eq!(patch.lines, Counts { count: 4, covered: 2 });
+        assert_eq!(
+            patch.uncovered,
+            [("src/modules/git.rs".to_string(), 14), ("src/modules/git.rs".to_string(), 17)]
+        );
+        assert_eq!(PatchCoverage::new(&coverage, &BTreeMap::new(), "/repo").lines.count, 0);
+    }
+    
+    #[test]
+    fn files_sort_and_roll_up_by_directory() {
+        let file = |name: &str, count: u64, covered: u64| FileCoverage {
+            name: name.to_string(),
+            summary: CoverageSummary {
+                lines: Counts { count, covered },
+                ..Default::default()
+            },
+            hits: BTreeMap::new(),
+        };
+        let files = vec![
+            file("/repo/src/modules/git.rs", 10, 9),
+            file("/repo/src/main.rs", 20, 5),
+            file("/repo/src/modules/api.rs", 10, 3),
+            file("/elsewhere/build.rs", 0, 0),
+        ];
+        
+        let names = |rows: Vec<CoverageRow>| rows.into_iter().map(|row| row.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(file_rows(&files, "/repo", CoverageSort::Lines)),
+            ["src/main.rs", "src/modules/api.rs", "src/modules/git.rs", "/elsewhere/build.rs"]
+        );
+        assert_eq!(names(file_rows(&files, "/repo", CoverageSort::Uncovered))[0], "src/main.rs");
+        
+        let tree = directory_tree(&files, "/repo");
+        let rows: Vec<(usize, &str, u64, Option<usize>)> = tree
+            .iter()
+            .map(|row| (row.depth, row.name.as_str(), row.summary.lines.covered, row.file))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (0, "elsewhere", 0, None),
+                (1, "build.rs", 0, Some(3)),
+                (0, "src", 17, None),
+                (1, "main.rs", 5, Some(1)),
+                (1, "modules", 12, None),
+                (2, "api.rs", 3, Some(2)),
+                (2, "git.rs", 9, Some(0)),
+            ]
+        );
+        assert_eq!(tree[2].summary.lines.count, 40);
+    }
+    
+    #[tokio::test]
+    async fn run_in_missing_directory_fails() {
+        let mut run = CoverageRun::spawn("/nonexistent/devdash");
+        let result = loop {
+            if let Some(result) = run.poll() {
+                break result;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        
+        assert!(result.unwrap_err().to_string().starts_with("failed to run cargo llvm-cov"));
+    }
+}
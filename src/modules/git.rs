@@ -1,22 +1,112 @@
 use anyhow::Result;
-use git2::Repository;
+use git2::{DiffOptions, Oid, Repository, Status, SubmoduleIgnore, SubmoduleStatus};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub struct GitStatus {
     pub branch: String,
+    pub default_branch: String,
+    pub head_sha: String,
     pub commits_ahead: usize,
     pub commits_behind: usize,
-    pub staged: usize,
-    pub unstaged: usize,
-    pub untracked: usize,
+    pub changes: StatusBreakdown,
     pub last_commit_message: String,
     pub last_commit_author: String,
+    pub submodules: Vec<SubmoduleInfo>,
+    pub worktrees: Vec<WorktreeInfo>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChangeCounts {
+    pub added: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    pub renamed: usize,
+    pub typechange: usize,
+}
+
+impl ChangeCounts {
+    pub fn total(&self) -> usize {
+        self.added + self.modified + self.deleted + self.renamed + self.typechange
+    }
+}
+
+/// Per-file classification of `git status`. Every file lands in exactly one
+/// bucket per side: `staged` compares HEAD to the index, `unstaged` compares
+/// the index to the working tree. Untracked, ignored and conflicted files are
+/// counted on their own and never in either side.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StatusBreakdown {
+    pub staged: ChangeCounts,
+    pub unstaged: ChangeCounts,
+    pub conflicted: usize,
+    pub untracked: usize,
+    pub ignored: usize,
+}
+
+impl StatusBreakdown {
+    pub fn record(&mut self, status: Status) {
+        if status.is_conflicted() {
+            self.conflicted += 1;
+            return;
+        }
+        if status.is_ignored() {
+            self.ignored += 1;
+            return;
+        }
+        if status.is_wt_new() {
+            self.untracked += 1;
+        }
+        
+        if status.is_index_renamed() {
+            self.staged.renamed += 1;
+        } else if status.is_index_new() {
+            self.staged.added += 1;
+        } else if status.is_index_deleted() {
+            self.staged.deleted += 1;
+        } else if status.is_index_typechange() {
+            self.staged.typechange += 1;
+        } else if status.is_index_modified() {
+            self.staged.modified += 1;
+        }
+        
+        if status.is_wt_renamed() {
+            self.unstaged.renamed += 1;
+        } else if status.is_wt_deleted() {
+            self.unstaged.deleted += 1;
+        } else if status.is_wt_typechange() {
+            self.unstaged.typechange += 1;
+        } else if status.is_wt_modified() {
+            self.unstaged.modified += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubmoduleState {
+    Clean,
+    OutOfDate,
+    Dirty,
+    Uninitialized,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubmoduleInfo {
+    pub path: String,
+    pub state: SubmoduleState,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorktreeInfo {
+    pub name: String,
+    pub path: String,
+    pub branch: String,
 }
 
 pub struct GitModule;
 
 impl GitModule {
-    pub fn get_status(repo_path: &str) -> Result<GitStatus> {
+    pub fn get_status(repo_path: &str, include_ignored: bool) -> Result<GitStatus> {
         let repo = Repository::open(repo_path)?;
         
         let head = repo.head()?;
@@ -25,7 +115,7 @@ impl GitModule {
         let mut commits_ahead = 0;
         let mut commits_behind = 0;
         
-        if let Ok(upstream) = repo.branch_upstream_name(&head.name().unwrap()) {
+        if let Ok(upstream) = repo.branch_upstream_name(head.name().unwrap()) {
             let upstream_ref = format!("refs/remotes/{}", upstream.as_str().unwrap_or(""));
             if let Ok(upstream_oid) = repo.refname_to_id(&upstream_ref) {
                 let (ahead, behind) = repo.graph_ahead_behind(head.target().unwrap(), upstream_oid)?;
@@ -34,27 +124,7 @@ impl GitModule {
             }
         }
         
-        let mut staged = 0;
-        let mut unstaged = 0;
-        let mut untracked = 0;
-        
-        let mut options = git2::StatusOptions::new();
-        options.include_untracked(true);
-        
-        if let Ok(statuses) = repo.statuses(Some(&mut options)) {
-            for entry in statuses.iter() {
-                let status = entry.status();
-                if status.is_index_new() || status.is_index_modified() || status.is_index_deleted() {
-                    staged += 1;
-                }
-                if status.is_wt_new() || status.is_wt_modified() || status.is_wt_deleted() {
-                    unstaged += 1;
-                }
-                if status.is_wt_new() {
-                    untracked += 1;
-                }
-            }
-        }
+        let changes = Self::get_changes(&repo, include_ignored)?;
         
         let last_commit_message = if let Ok(commit) = head.peel_to_commit() {
             commit.message().unwrap_or("").lines().next().unwrap_or("").to_string()
@@ -70,13 +140,323 @@ impl GitModule {
         
         Ok(GitStatus {
             branch: branch_name,
+            default_branch: Self::default_branch(&repo),
+            head_sha: head.target().map(|oid| oid.to_string()).unwrap_or_default(),
             commits_ahead,
             commits_behind,
-            staged,
-            unstaged,
-            untracked,
+            changes,
             last_commit_message,
             last_commit_author,
+            submodules: Self::get_submodules(&repo),
+            worktrees: Self::get_worktrees(&repo),
         })
     }
+    
+    /// The branch `origin/HEAD` points at, falling back to `main` when the
+    /// remote HEAD has never been fetched.
+    fn default_branch(repo: &Repository) -> String {
+        repo.find_reference("refs/remotes/origin/HEAD")
+            .ok()
+            .and_then(|r| r.symbolic_target().map(|t| t.to_string()))
+            .and_then(|t| t.strip_prefix("refs/remotes/origin/").map(|b| b.to_string()))
+            .unwrap_or_else(|| "main".to_string())
+    }
+    
+    /// Where the current branch forked from `branch`. The remote-tracking
+    /// ref is preferred, since the local branch is often stale.
+    pub fn merge_base(repo_path: &str, branch: &str) -> Option<String> {
+        let repo = Repository::open(repo_path).ok()?;
+        let head = repo.head().ok()?.target()?;
+        let base = [format!("refs/remotes/origin/{}", branch), format!("refs/heads/{}", branch)]
+            .iter()
+            .find_map(|name| repo.refname_to_id(name).ok())?;
+        repo.merge_base(head, base).ok().map(|oid| oid.to_string())
+    }
+    
+    /// Line numbers added or changed in each file, compared with `base` or
+    /// with HEAD when there is none. Staged, unstaged and untracked changes
+    /// all count.
+    pub fn changed_lines(repo_path: &str, base: Option<&str>) -> Result<BTreeMap<String, Vec<u32>>> {
+        let repo = Repository::open(repo_path)?;
+        let tree = match base {
+            Some(sha) => repo.find_commit(Oid::from_str(sha)?)?.tree()?,
+            None => repo.head()?.peel_to_tree()?,
+        };
+        let mut options = DiffOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true)
+            .context_lines(0);
+        let diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))?;
+        
+        let mut changed: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        diff.foreach(
+            &mut |_, _| true,
+            None,
+            None,
+            Some(&mut |delta, _, line| {
+                if let (Some(path), Some(number)) = (delta.new_file().path(), line.new_lineno()) {
+                    if line.origin() == '+' {
+                        changed.entry(path.to_string_lossy().into_owned()).or_default().push(number);
+                    }
+                }
+                true
+            }),
+        )?;
+        Ok(changed)
+    }
+    
+    fn get_changes(repo: &Repository, include_ignored: bool) -> Result<StatusBreakdown> {
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(include_ignored)
+            .renames_head_to_index(true)
+            .renames_index_to_workdir(true);
+        
+        let mut changes = StatusBreakdown::default();
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            changes.record(entry.status());
+        }
+        Ok(changes)
+    }
+    
+    fn get_submodules(repo: &Repository) -> Vec<SubmoduleInfo> {
+        let Ok(submodules) = repo.submodules() else {
+            return Vec::new();
+        };
+        
+        submodules
+            .iter()
+            .filter_map(|sm| {
+                let name = sm.name()?;
+                let status = repo.submodule_status(name, SubmoduleIgnore::None).ok()?;
+                
+                let state = if status.contains(SubmoduleStatus::WD_UNINITIALIZED)
+                    || !status.contains(SubmoduleStatus::IN_WD)
+                {
+                    SubmoduleState::Uninitialized
+                } else if status.contains(SubmoduleStatus::WD_MODIFIED) {
+                    SubmoduleState::OutOfDate
+                } else if status.intersects(
+                    SubmoduleStatus::WD_INDEX_MODIFIED
+                        | SubmoduleStatus::WD_WD_MODIFIED
+                        | SubmoduleStatus::WD_UNTRACKED,
+                ) {
+                    SubmoduleState::Dirty
+                } else {
+                    SubmoduleState::Clean
+                };
+                
+                Some(SubmoduleInfo {
+                    path: sm.path().to_string_lossy().to_string(),
+                    state,
+                })
+            })
+            .collect()
+    }
+    
+    fn get_worktrees(repo: &Repository) -> Vec<WorktreeInfo> {
+        let Ok(names) = repo.worktrees() else {
+            return Vec::new();
+        };
+        
+        names
+            .iter()
+            .flatten()
+            .filter_map(|name| {
+                let worktree = repo.find_worktree(name).ok()?;
+                let branch = match Repository::open_from_worktree(&worktree) {
+                    Ok(wt_repo) => match wt_repo.head() {
+                        Ok(head) => head.shorthand().unwrap_or("detached").to_string(),
+                        Err(_) => "unknown".to_string(),
+                    },
+                    Err(_) => "missing".to_string(),
+                };
+                
+                Some(WorktreeInfo {
+                    name: name.to_string(),
+                    path: worktree.path().to_string_lossy().to_string(),
+                    branch,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Index};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+    
+    fn fixture(files: &[(&str, &str)]) -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        let mut index = repo.index().unwrap();
+        for (name, _) in files {
+            index.add_path(Path::new(name)).unwrap();
+        }
+        commit_index(&repo, &mut index, "initial");
+        (dir, repo)
+    }
+    
+    fn commit_index(repo: &Repository, index: &mut Index, message: &str) {
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap();
+    }
+    
+    fn status(dir: &TempDir, include_ignored: bool) -> StatusBreakdown {
+        GitModule::get_status(dir.path().to_str().unwrap(), include_ignored)
+            .unwrap()
+            .changes
+    }
+    
+    #[test]
+    fn untracked_file_is_not_unstaged() {
+        let (dir, _repo) = fixture(&[("a.txt", "a\n")]);
+        fs::write(dir.path().join("new.txt"), "new\n").unwrap();
+        
+        let changes = status(&dir, false);
+        assert_eq!(changes.untracked, 1);
+        assert_eq!(changes.unstaged.total(), 0);
+        assert_eq!(changes.staged.total(), 0);
+    }
+    
+    #[test]
+    fn staged_and_unstaged_changes_are_split() {
+        let (dir, repo) = fixture(&[("a.txt", "a\n"), ("b.txt", "b\n"), ("c.txt", "c\n")]);
+        fs::write(dir.path().join("a.txt"), "changed\n").unwrap();
+        fs::write(dir.path().join("added.txt"), "added\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.add_path(Path::new("added.txt")).unwrap();
+        index.write().unwrap();
+        
+        fs::write(dir.path().join("b.txt"), "changed\n").unwrap();
+        fs::remove_file(dir.path().join("c.txt")).unwrap();
+        
+        let changes = status(&dir, false);
+        assert_eq!(changes.staged, ChangeCounts { added: 1, modified: 1, ..Default::default() });
+        assert_eq!(changes.unstaged, ChangeCounts { modified: 1, deleted: 1, ..Default::default() });
+        assert_eq!(changes.untracked, 0);
+    }
+    
+    #[test]
+    fn staged_rename_is_counted_once() {
+        let (dir, repo) = fixture(&[("old.txt", "some content that is long enough to match\n")]);
+        fs::rename(dir.path().join("old.txt"), dir.path().join("new.txt")).unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("old.txt")).unwrap();
+        index.add_path(Path::new("new.txt")).unwrap();
+        index.write().unwrap();
+        
+        let changes = status(&dir, false);
+        assert_eq!(changes.staged, ChangeCounts { renamed: 1, ..Default::default() });
+        assert_eq!(changes.unstaged.total(), 0);
+        assert_eq!(changes.untracked, 0);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn file_replaced_by_symlink_is_typechange() {
+        let (dir, _repo) = fixture(&[("a.txt", "a\n"), ("b.txt", "b\n")]);
+        fs::remove_file(dir.path().join("a.txt")).unwrap();
+        std::os::unix::fs::symlink("b.txt", dir.path().join("a.txt")).unwrap();
+        
+        let changes = status(&dir, false);
+        assert_eq!(changes.unstaged, ChangeCounts { typechange: 1, ..Default::default() });
+    }
+    
+    #[test]
+    fn ignored_files_are_reported_only_on_request() {
+        let (dir, _repo) = fixture(&[(".gitignore", "*.log\n")]);
+        fs::write(dir.path().join("debug.log"), "noise\n").unwrap();
+        
+        assert_eq!(status(&dir, false).ignored, 0);
+        let changes = status(&dir, true);
+        assert_eq!(changes.ignored, 1);
+        assert_eq!(changes.untracked, 0);
+    }
+    
+    #[test]
+    fn merge_base_prefers_the_remote_branch() {
+        let (dir, repo) = fixture(&[("a.txt", "a\n")]);
+        let path = dir.path().to_str().unwrap();
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("trunk", &base, false).unwrap();
+        
+        fs::write(dir.path().join("a.txt"), "feature\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        commit_index(&repo, &mut index, "feature");
+        let head = repo.head().unwrap().target().unwrap();
+        assert_eq!(GitModule::merge_base(path, "trunk"), Some(base.id().to_string()));
+        
+        repo.reference("refs/remotes/origin/trunk", head, false, "fetch").unwrap();
+        assert_eq!(GitModule::merge_base(path, "trunk"), Some(head.to_string()));
+        assert_eq!(GitModule::merge_base(path, "missing"), None);
+    }
+    
+    #[test]
+    fn changed_lines_cover_commits_and_working_tree() {
+        let (dir, repo) = fixture(&[("a.txt", "1\n2\n3\n")]);
+        let path = dir.path().to_str().unwrap();
+        let base = repo.head().unwrap().target().unwrap().to_string();
+        
+        fs::write(dir.path().join("a.txt"), "1\ntwo\n3\n4\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        commit_index(&repo, &mut index, "change a");
+        fs::write(dir.path().join("a.txt"), "one\ntwo\n3\n4\n").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/new.rs"), "fn main() {}\n").unwrap();
+        
+        let working_tree = GitModule::changed_lines(path, None).unwrap();
+        assert_eq!(working_tree["a.txt"], [1]);
+        assert_eq!(working_tree["src/new.rs"], [1]);
+        
+        let branch = GitModule::changed_lines(path, Some(&base)).unwrap();
+        assert_eq!(branch["a.txt"], [1, 2, 4]);
+    }
+    
+    #[test]
+    fn merge_conflict_is_counted_as_conflicted() {
+        let (dir, repo) = fixture(&[("a.txt", "base\n")]);
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("other", &base, false).unwrap();
+        
+        fs::write(dir.path().join("a.txt"), "ours\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        commit_index(&repo, &mut index, "ours");
+        
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        fs::write(dir.path().join("a.txt"), "theirs\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        commit_index(&repo, &mut index, "theirs");
+        
+        let master = repo.find_reference("refs/heads/master")
+            .or_else(|_| repo.find_reference("refs/heads/main"))
+            .unwrap();
+        let annotated = repo.reference_to_annotated_commit(&master).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+        
+        let changes = status(&dir, false);
+        assert_eq!(changes.conflicted, 1);
+        assert_eq!(changes.staged.total() + changes.unstaged.total(), 0);
+    }
 }
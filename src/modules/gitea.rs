@@ -0,0 +1,381 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use super::api::GiteaApi;
+use super::build::{github_status, BuildAction, BuildInfo, BuildStatus, GitHubJobsResponse, JobInfo};
+
+#[derive(Deserialize)]
+struct GiteaRunsResponse {
+    workflow_runs: Vec<GiteaRun>,
+}
+
+/// A run from the GitHub-compatible `actions/runs` endpoint (Gitea 1.24+).
+#[derive(Deserialize)]
+struct GiteaRun {
+    id: u64,
+    status: String,
+    conclusion: Option<String>,
+    #[serde(default)]
+    display_title: String,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    head_branch: String,
+    head_sha: String,
+    started_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct GiteaTasksResponse {
+    workflow_runs: Vec<GiteaTask>,
+}
+
+/// One job attempt from the older `actions/tasks` endpoint, which is all
+/// Forgejo and Gitea before 1.24 offer. Tasks of the same run share a
+/// `run_number`.
+#[derive(Deserialize)]
+struct GiteaTask {
+    id: u64,
+    name: String,
+    status: String,
+    run_number: u64,
+    #[serde(default)]
+    head_branch: String,
+    head_sha: String,
+    #[serde(default)]
+    display_title: String,
+    #[serde(default)]
+    workflow_id: String,
+    run_started_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+fn task_status(status: &str) -> BuildStatus {
+    match status {
+        "success" => BuildStatus::Success,
+        "failure" => BuildStatus::Failure,
+        "running" => BuildStatus::Running,
+        "waiting" | "blocked" => BuildStatus::Queued,
+        "cancelled" => BuildStatus::Cancelled,
+        "skipped" => BuildStatus::Skipped,
+        _ => BuildStatus::Unknown,
+    }
+}
+
+/// `path` looks like `.gitea/workflows/ci.yml@refs/heads/main`.
+fn workflow_name(path: &str) -> String {
+    let file = path.split('@').next().unwrap_or(path);
+    file.rsplit('/').next().unwrap_or(file).to_string()
+}
+
+fn parse_runs(data: GiteaRunsResponse) -> Vec<BuildInfo> {
+    data.workflow_runs
+        .into_iter()
+        .map(|run| BuildInfo {
+            id: run.id,
+            status: github_status(&run.status, run.conclusion.as_deref()),
+            name: workflow_name(&run.path),
+            branch: run.head_branch,
+            started_at: run.started_at,
+            finished_at: run.completed_at,
+            commit_sha: run.head_sha.chars().take(7).collect(),
+            message: run.display_title,
+        })
+        .collect()
+}
+
+/// Folds tasks into one entry per run: failed if any job failed, running
+/// while any job is still running or queued, successful once every job
+/// succeeded or was skipped. The run starts with its earliest job and only
+/// finishes once no job is active.
+fn runs_from_tasks(tasks: &[GiteaTask], branch: Option<&str>) -> Vec<BuildInfo> {
+    let mut builds: Vec<BuildInfo> = Vec::new();
+    for task in tasks {
+        if branch.is_some_and(|branch| branch != task.head_branch) {
+            continue;
+        }
+        let status = task_status(&task.status);
+        let finished_at = task.updated_at.filter(|_| !status.is_active());
+        match builds.iter_mut().find(|build| build.id == task.run_number) {
+            Some(build) => {
+                build.status = match (build.status, status) {
+                    (BuildStatus::Failure, _) | (_, BuildStatus::Failure) => BuildStatus::Failure,
+                    (a, b) if a.is_active() || b.is_active() => BuildStatus::Running,
+                    (BuildStatus::Cancelled, _) | (_, BuildStatus::Cancelled) => BuildStatus::Cancelled,
+                    (BuildStatus::Skipped, other) | (other, BuildStatus::Skipped) => other,
+                    (BuildStatus::Success, BuildStatus::Success) => BuildStatus::Success,
+                    _ => BuildStatus::Unknown,
+                };
+                build.started_at = match (build.started_at, task.run_started_at) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                build.finished_at = if build.status.is_active() {
+                    None
+                } else {
+                    build.finished_at.max(finished_at)
+                };
+            }
+            None => builds.push(BuildInfo {
+                id: task.run_number,
+                status,
+                name: task.workflow_id.clone(),
+                branch: task.head_branch.clone(),
+                started_at: task.run_started_at,
+                finished_at,
+                commit_sha: task.head_sha.chars().take(7).collect(),
+                message: task.display_title.clone(),
+            }),
+        }
+    }
+    builds.truncate(10);
+    builds
+}
+
+fn jobs_from_tasks(tasks: Vec<GiteaTask>, run_number: u64) -> Vec<JobInfo> {
+    let mut jobs: Vec<JobInfo> = tasks
+        .into_iter()
+        .filter(|task| task.run_number == run_number)
+        .map(|task| {
+            let status = task_status(&task.status);
+            let finished = !status.is_active();
+            JobInfo {
+                id: task.id,
+                name: task.name,
+                status,
+                started_at: task.run_started_at,
+                completed_at: if finished { task.updated_at } else { None },
+                steps: Vec::new(),
+            }
+        })
+        .collect();
+    jobs.reverse();
+    jobs
+}
+
+pub struct GiteaModule;
+
+impl GiteaModule {
+    /// `tasks_only` is set once the server answers `actions/runs` with a 404,
+    /// so later refreshes go straight to the tasks endpoint.
+    pub async fn get_status(
+        api: &GiteaApi,
+        repo: &str,
+        branch: Option<&str>,
+        tasks_only: &OnceLock<bool>,
+    ) -> Result<Vec<BuildInfo>> {
+        if tasks_only.get() != Some(&true) {
+            let response = api
+                .get(&format!("/repos/{}/actions/runs?limit=10", repo))
+                .query(&[("branch", branch)])
+                .send()
+                .await?;
+            
+            if response.status() != StatusCode::NOT_FOUND {
+                tasks_only.get_or_init(|| false);
+                let data: GiteaRunsResponse = response.error_for_status()?.json().await?;
+                return Ok(parse_runs(data));
+            }
+            tasks_only.get_or_init(|| true);
+        }
+        
+        let tasks = Self::get_tasks(api, repo).await?;
+        Ok(runs_from_tasks(&tasks, branch))
+    }
+    
+    pub async fn get_jobs(api: &GiteaApi, repo: &str, run_id: u64, tasks_only: &OnceLock<bool>) -> Result<Vec<JobInfo>> {
+        if tasks_only.get() != Some(&true) {
+            let response = api
+                .get(&format!("/repos/{}/actions/runs/{}/jobs", repo, run_id))
+                .send()
+                .await?;
+            
+            if response.status() != StatusCode::NOT_FOUND {
+                let data: GitHubJobsResponse = response.error_for_status()?.json().await?;
+                return Ok(data.into_jobs());
+            }
+        }
+        
+        let tasks = Self::get_tasks(api, repo).await?;
+        Ok(jobs_from_tasks(tasks, run_id))
+    }
+    
+    async fn get_tasks(api: &GiteaApi, repo: &str) -> Result<Vec<GiteaTask>> {
+        let response = api
+            .get(&format!("/repos/{}/actions/tasks?limit=50", repo))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        let data: GiteaTasksResponse = response.json().await?;
+        Ok(data.workflow_runs)
+    }
+    
+    /// Only workflow dispatch is exposed by the Gitea API; runs can be
+    /// re-run or cancelled from the web UI.
+    pub async fn run_action(api: &GiteaApi, repo: &str, action: &BuildAction) -> Result<()> {
+        let BuildAction::Dispatch { workflow, git_ref, inputs } = action else {
+            return Err(anyhow!("not supported by Gitea"));
+        };
+        
+        api.post(&format!("/repos/{}/actions/workflows/{}/dispatches", repo, workflow))
+            .json(&serde_json::json!({ "ref": git_ref, "inputs": inputs }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+    
+    pub async fn get_job_log(api: &GiteaApi, repo: &str, job_id: u64) -> Result<String> {
+        let response = api
+            .get(&format!("/repos/{}/actions/jobs/{}/logs", repo, job_id))
+            .send()
+            .await?;
+        
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(anyhow!("job logs need Gitea 1.24 or later"));
+        }
+        Ok(response.error_for_status()?.text().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    const RUNS: &str = include_str!("../../tests/fixtures/ci/gitea_runs.json");
+    const JOBS: &str = include_str!("../../tests/fixtures/ci/gitea_jobs.json");
+    const TASKS: &str = include_str!("../../tests/fixtures/ci/forgejo_tasks.json");
+    
+    #[test]
+    fn runs_fixture() {
+        let builds = parse_runs(serde_json::from_str(RUNS).unwrap());
+        
+        assert_eq!(builds.len(), 2);
+        assert_eq!(builds[0].id, 57);
+        assert_eq!(builds[0].name, "ci.yml");
+        assert_eq!(builds[0].commit_sha, "5b3c1a9");
+        assert_eq!(builds[0].message, "Fix flaky login test");
+        assert!(matches!(builds[0].status, BuildStatus::Failure));
+        assert!(matches!(builds[1].status, BuildStatus::Running));
+    }
+    
+    #[test]
+    fn jobs_fixture() {
+        let data: GitHubJobsResponse = serde_json::from_str(JOBS).unwrap();
+        let jobs = data.into_jobs();
+        
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[1].name, "test");
+        assert!(matches!(jobs[1].status, BuildStatus::Failure));
+        assert_eq!(jobs[1].steps.len(), 3);
+        assert!(matches!(jobs[1].steps[2].status, BuildStatus::Failure));
+    }
+    
+    #[test]
+    fn tasks_fixture_is_grouped_by_run() {
+        let data: GiteaTasksResponse = serde_json::from_str(TASKS).unwrap();
+        let builds = runs_from_tasks(&data.workflow_runs, None);
+        
+        assert_eq!(builds.iter().map(|b| b.id).collect::<Vec<_>>(), vec![12, 11]);
+        assert!(matches!(builds[0].status, BuildStatus::Running));
+        assert!(matches!(builds[1].status, BuildStatus::Failure));
+        assert_eq!(builds[1].name, "ci.yml");
+        
+        let on_main = runs_from_tasks(&data.workflow_runs, Some("main"));
+        assert_eq!(on_main.iter().map(|b| b.id).collect::<Vec<_>>(), vec![11]);
+        
+        let jobs = jobs_from_tasks(data.workflow_runs, 11);
+        assert_eq!(jobs.iter().map(|j| j.name.as_str()).collect::<Vec<_>>(), vec!["lint", "test"]);
+        assert!(jobs[1].completed_at.is_some());
+    }
+    
+    #[test]
+    fn tasks_merge_skipped_jobs_and_timestamps() {
+        let task = |id: u64, run: u64, status: &str, started: &str, updated: &str| {
+            serde_json::json!({
+                "id": id,
+                "name": format!("job-{}", id),
+                "status": status,
+                "run_number": run,
+                "head_branch": "main",
+                "head_sha": "abcdef1234567",
+                "workflow_id": "ci.yml",
+                "run_started_at": started,
+                "updated_at": updated
+            })
+        };
+        let tasks: Vec<GiteaTask> = serde_json::from_value(serde_json::json!([
+            task(6, 2, "success", "2025-03-05T10:01:00Z", "2025-03-05T10:05:00Z"),
+            task(5, 2, "skipped", "2025-03-05T10:01:00Z", "2025-03-05T10:01:00Z"),
+            task(4, 2, "running", "2025-03-05T10:00:00Z", "2025-03-05T10:03:00Z"),
+            task(3, 1, "success", "2025-03-05T09:01:00Z", "2025-03-05T09:06:00Z"),
+            task(2, 1, "skipped", "2025-03-05T09:00:00Z", "2025-03-05T09:00:00Z"),
+        ]))
+        .unwrap();
+        let builds = runs_from_tasks(&tasks, None);
+        
+        assert!(matches!(builds[0].status, BuildStatus::Running));
+        assert_eq!(builds[0].started_at.unwrap().to_rfc3339(), "2025-03-05T10:00:00+00:00");
+        assert_eq!(builds[0].finished_at, None);
+        
+        assert!(matches!(builds[1].status, BuildStatus::Success));
+        assert_eq!(builds[1].started_at.unwrap().to_rfc3339(), "2025-03-05T09:00:00+00:00");
+        assert_eq!(builds[1].finished_at.unwrap().to_rfc3339(), "2025-03-05T09:06:00+00:00");
+    }
+    
+    #[tokio::test]
+    async fn forgejo_without_runs_endpoint_falls_back_to_tasks() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/acme/app/actions/runs"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/acme/app/actions/tasks"))
+            .and(header("Authorization", "token fj-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(TASKS))
+            .expect(3)
+            .mount(&server)
+            .await;
+        
+        let api = GiteaApi::new(reqwest::Client::new(), &format!("{}/api/v1", server.uri()), Some("fj-token"));
+        let tasks_only = OnceLock::new();
+        let builds = GiteaModule::get_status(&api, "acme/app", None, &tasks_only).await.unwrap();
+        assert_eq!(builds.len(), 2);
+        assert_eq!(tasks_only.get(), Some(&true));
+        
+        // Later refreshes and job lookups skip the missing endpoint.
+        GiteaModule::get_status(&api, "acme/app", None, &tasks_only).await.unwrap();
+        let jobs = GiteaModule::get_jobs(&api, "acme/app", 11, &tasks_only).await.unwrap();
+        assert_eq!(jobs.len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn gitea_runs_are_filtered_by_branch() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/repos/acme/app/actions/runs"))
+            .and(query_param("branch", "main"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(RUNS))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = GiteaApi::new(reqwest::Client::new(), &format!("{}/api/v1", server.uri()), None);
+        let tasks_only = OnceLock::new();
+        let builds = GiteaModule::get_status(&api, "acme/app", Some("main"), &tasks_only).await.unwrap();
+        assert_eq!(builds[0].id, 57);
+        assert_eq!(tasks_only.get(), Some(&false));
+        
+        assert!(GiteaModule::run_action(&api, "acme/app", &BuildAction::Cancel(57)).await.is_err());
+    }
+}
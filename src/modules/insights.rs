@@ -0,0 +1,284 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use git2::{DiffOptions, Oid, Patch, Repository, Sort};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct CommitStat {
+    pub date: NaiveDate,
+    pub author: String,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub files: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GitInsights {
+    pub weeks: usize,
+    pub commits: Vec<CommitStat>,
+    head: Option<Oid>,
+}
+
+impl GitInsights {
+    pub fn new(weeks: usize) -> Self {
+        GitInsights {
+            weeks,
+            commits: Vec::new(),
+            head: None,
+        }
+    }
+    
+    /// Walks only the commits added since the last call. If HEAD moved to a
+    /// commit that does not descend from the cached one (rebase, reset,
+    /// branch switch) the cache is rebuilt from scratch.
+    pub fn update(&mut self, repo_path: &str) -> Result<()> {
+        let repo = Repository::open(repo_path)?;
+        let head = repo.head()?.peel_to_commit()?.id();
+        
+        if self.head == Some(head) {
+            self.prune();
+            return Ok(());
+        }
+        
+        let incremental = match self.head {
+            Some(old) => repo.graph_descendant_of(head, old).unwrap_or(false),
+            None => false,
+        };
+        if !incremental {
+            self.commits.clear();
+        }
+        
+        let cutoff = self.cutoff();
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TIME)?;
+        walk.push(head)?;
+        if let (true, Some(old)) = (incremental, self.head) {
+            walk.hide(old)?;
+        }
+        
+        let mut fresh = Vec::new();
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            let time = DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_default();
+            if time.date_naive() < cutoff {
+                break;
+            }
+            fresh.push(Self::commit_stat(&repo, &commit, time.date_naive())?);
+        }
+        
+        fresh.append(&mut self.commits);
+        self.commits = fresh;
+        self.head = Some(head);
+        self.prune();
+        Ok(())
+    }
+    
+    fn commit_stat(repo: &Repository, commit: &git2::Commit, date: NaiveDate) -> Result<CommitStat> {
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        
+        let mut options = DiffOptions::new();
+        options.context_lines(0);
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?;
+        
+        let mut insertions = 0;
+        let mut deletions = 0;
+        let mut files = Vec::new();
+        for idx in 0..diff.deltas().len() {
+            let Some(patch) = Patch::from_diff(&diff, idx)? else {
+                continue;
+            };
+            let (_, added, removed) = patch.line_stats()?;
+            insertions += added;
+            deletions += removed;
+            
+            let delta = patch.delta();
+            if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+                files.push((path.to_string_lossy().to_string(), added + removed));
+            }
+        }
+        
+        Ok(CommitStat {
+            date,
+            author: commit.author().name().unwrap_or("unknown").to_string(),
+            insertions,
+            deletions,
+            files,
+        })
+    }
+    
+    fn cutoff(&self) -> NaiveDate {
+        Utc::now().date_naive() - Duration::weeks(self.weeks as i64)
+    }
+    
+    fn prune(&mut self) {
+        let cutoff = self.cutoff();
+        self.commits.retain(|c| c.date >= cutoff);
+    }
+    
+    /// The oldest day `commits_per_day` covers.
+    pub fn first_day(&self) -> NaiveDate {
+        Utc::now().date_naive() - Duration::days(self.weeks as i64 * 7 - 1)
+    }
+    
+    /// Commit counts for each day of the window, oldest first.
+    pub fn commits_per_day(&self) -> Vec<u64> {
+        let days = self.weeks * 7;
+        let today = Utc::now().date_naive();
+        let mut buckets = vec![0u64; days];
+        for commit in &self.commits {
+            let age = (today - commit.date).num_days();
+            if age >= 0 && (age as usize) < days {
+                buckets[days - 1 - age as usize] += 1;
+            }
+        }
+        buckets
+    }
+    
+    pub fn top_authors(&self, limit: usize) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for commit in &self.commits {
+            *counts.entry(&commit.author).or_default() += 1;
+        }
+        Self::ranked(counts, limit)
+    }
+    
+    pub fn most_churned_files(&self, limit: usize) -> Vec<(String, usize)> {
+        let mut churn: HashMap<&str, usize> = HashMap::new();
+        for commit in &self.commits {
+            for (path, lines) in &commit.files {
+                *churn.entry(path).or_default() += lines;
+            }
+        }
+        Self::ranked(churn, limit)
+    }
+    
+    /// Mean number of changed lines (insertions plus deletions) per commit.
+    pub fn average_commit_size(&self) -> f64 {
+        if self.commits.is_empty() {
+            return 0.0;
+        }
+        let total: usize = self
+            .commits
+            .iter()
+            .map(|c| c.insertions + c.deletions)
+            .sum();
+        total as f64 / self.commits.len() as f64
+    }
+    
+    fn ranked(counts: HashMap<&str, usize>, limit: usize) -> Vec<(String, usize)> {
+        let mut ranked: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{ResetType, Signature};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+    
+    fn commit_file(dir: &TempDir, repo: &Repository, name: &str, days_ago: i64) -> Oid {
+        fs::write(dir.path().join(name), format!("{}\n", name)).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(name)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let time = git2::Time::new((Utc::now() - Duration::days(days_ago)).timestamp(), 0);
+        let sig = Signature::new("Test", "test@example.com", &time).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &parents).unwrap()
+    }
+    
+    fn files(insights: &GitInsights) -> Vec<String> {
+        let mut files: Vec<String> = insights
+            .commits
+            .iter()
+            .flat_map(|c| c.files.iter().map(|(path, _)| path.clone()))
+            .collect();
+        files.sort();
+        files
+    }
+    
+    #[test]
+    fn update_appends_new_commits() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+        commit_file(&dir, &repo, "a.txt", 0);
+        
+        let mut insights = GitInsights::new(4);
+        insights.update(path).unwrap();
+        assert_eq!(files(&insights), vec!["a.txt"]);
+        
+        commit_file(&dir, &repo, "b.txt", 0);
+        commit_file(&dir, &repo, "c.txt", 0);
+        insights.update(path).unwrap();
+        assert_eq!(files(&insights), vec!["a.txt", "b.txt", "c.txt"]);
+        
+        insights.update(path).unwrap();
+        assert_eq!(insights.commits.len(), 3);
+        assert_eq!(insights.commits_per_day().iter().sum::<u64>(), 3);
+    }
+    
+    #[test]
+    fn update_stops_at_the_window_cutoff() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        commit_file(&dir, &repo, "old.txt", 60);
+        commit_file(&dir, &repo, "new.txt", 1);
+        
+        let mut insights = GitInsights::new(4);
+        insights.update(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(files(&insights), vec!["new.txt"]);
+    }
+    
+    #[test]
+    fn update_rebuilds_after_rewritten_history() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+        let first = commit_file(&dir, &repo, "a.txt", 0);
+        commit_file(&dir, &repo, "b.txt", 0);
+        
+        let mut insights = GitInsights::new(4);
+        insights.update(path).unwrap();
+        assert_eq!(files(&insights), vec!["a.txt", "b.txt"]);
+        
+        let first = repo.find_object(first, None).unwrap();
+        repo.reset(&first, ResetType::Hard, None).unwrap();
+        commit_file(&dir, &repo, "c.txt", 0);
+        insights.update(path).unwrap();
+        assert_eq!(files(&insights), vec!["a.txt", "c.txt"]);
+    }
+    
+    #[test]
+    fn update_rebuilds_after_branch_switch() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let path = dir.path().to_str().unwrap();
+        let first = commit_file(&dir, &repo, "a.txt", 0);
+        repo.branch("other", &repo.find_commit(first).unwrap(), false).unwrap();
+        commit_file(&dir, &repo, "b.txt", 0);
+        
+        let mut insights = GitInsights::new(4);
+        insights.update(path).unwrap();
+        assert_eq!(insights.commits.len(), 2);
+        
+        repo.set_head("refs/heads/other").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        insights.update(path).unwrap();
+        assert_eq!(files(&insights), vec!["a.txt"]);
+    }
+}
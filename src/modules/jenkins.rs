@@ -0,0 +1,364 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::api::JenkinsApi;
+use super::build::{BuildAction, BuildInfo, BuildStatus, JobInfo};
+
+const BUILDS_TREE: &str = "builds[number,result,inProgress,timestamp,duration,\
+actions[lastBuiltRevision[SHA1,branch[name]]],changeSets[items[msg]]]{0,20}";
+
+#[derive(Deserialize)]
+struct JenkinsJob {
+    #[serde(default)]
+    builds: Vec<JenkinsBuild>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JenkinsBuild {
+    number: u64,
+    result: Option<String>,
+    #[serde(default)]
+    in_progress: bool,
+    timestamp: i64,
+    #[serde(default)]
+    duration: i64,
+    #[serde(default)]
+    actions: Vec<JenkinsAction>,
+    #[serde(default)]
+    change_sets: Vec<JenkinsChangeSet>,
+}
+
+/// Most entries of `actions` are empty objects; the git plugin's build data
+/// carries the revision that was built.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JenkinsAction {
+    last_built_revision: Option<JenkinsRevision>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JenkinsRevision {
+    #[serde(rename = "SHA1")]
+    sha1: String,
+    #[serde(default)]
+    branch: Vec<JenkinsBranch>,
+}
+
+#[derive(Deserialize)]
+struct JenkinsBranch {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct JenkinsChangeSet {
+    #[serde(default)]
+    items: Vec<JenkinsChange>,
+}
+
+#[derive(Deserialize)]
+struct JenkinsChange {
+    msg: String,
+}
+
+/// Stage breakdown from the Pipeline Stage View plugin (`wfapi/describe`).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JenkinsRunDescription {
+    #[serde(default)]
+    stages: Vec<JenkinsStage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JenkinsStage {
+    id: String,
+    name: String,
+    status: String,
+    start_time_millis: i64,
+    #[serde(default)]
+    duration_millis: i64,
+}
+
+fn build_status(result: Option<&str>, in_progress: bool) -> BuildStatus {
+    match (result, in_progress) {
+        (_, true) => BuildStatus::Running,
+        (Some("SUCCESS"), _) => BuildStatus::Success,
+        (Some("FAILURE") | Some("UNSTABLE"), _) => BuildStatus::Failure,
+        (Some("ABORTED"), _) => BuildStatus::Cancelled,
+        (Some("NOT_BUILT"), _) => BuildStatus::Skipped,
+        _ => BuildStatus::Unknown,
+    }
+}
+
+fn stage_status(status: &str) -> BuildStatus {
+    match status {
+        "SUCCESS" => BuildStatus::Success,
+        "FAILED" | "UNSTABLE" => BuildStatus::Failure,
+        "IN_PROGRESS" => BuildStatus::Running,
+        "PAUSED_PENDING_INPUT" => BuildStatus::ActionRequired,
+        "NOT_EXECUTED" => BuildStatus::Skipped,
+        "ABORTED" => BuildStatus::Cancelled,
+        _ => BuildStatus::Unknown,
+    }
+}
+
+fn from_millis(millis: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(millis)
+}
+
+/// Jenkins reports branches as remote refs, e.g. `refs/remotes/origin/main`
+/// or `origin/main`.
+fn local_branch(name: &str) -> &str {
+    if let Some(branch) = name.strip_prefix("refs/heads/") {
+        return branch;
+    }
+    let name = name.strip_prefix("refs/remotes/").unwrap_or(name);
+    name.split_once('/').map_or(name, |(_, branch)| branch)
+}
+
+/// Jenkins has no server-side branch filter on a job's builds, so builds
+/// are matched against the branch of the revision they built.
+fn parse_builds(job: JenkinsJob, job_name: &str, branch: Option<&str>) -> Vec<BuildInfo> {
+    job.builds
+        .into_iter()
+        .filter_map(|build| {
+            let revision = build.actions.iter().find_map(|a| a.last_built_revision.as_ref());
+            if let Some(branch) = branch {
+                let built = revision
+                    .map(|r| r.branch.iter().any(|b| local_branch(&b.name) == branch))
+                    .unwrap_or(false);
+                if !built {
+                    return None;
+                }
+            }
+            let started_at = from_millis(build.timestamp);
+            Some(BuildInfo {
+                id: build.number,
+                status: build_status(build.result.as_deref(), build.in_progress),
+                name: job_name.to_string(),
+                branch: revision
+                    .and_then(|r| r.branch.first())
+                    .map(|b| local_branch(&b.name).to_string())
+                    .unwrap_or_default(),
+                started_at,
+                finished_at: if build.in_progress {
+                    None
+                } else {
+                    started_at.map(|start| start + Duration::milliseconds(build.duration))
+                },
+                commit_sha: revision.map(|r| r.sha1.chars().take(7).collect()).unwrap_or_default(),
+                message: build
+                    .change_sets
+                    .iter()
+                    .flat_map(|set| set.items.last())
+                    .last()
+                    .map(|change| change.msg.clone())
+                    .unwrap_or_default(),
+            })
+        })
+        .take(10)
+        .collect()
+}
+
+fn parse_stages(description: JenkinsRunDescription) -> Vec<JobInfo> {
+    description
+        .stages
+        .into_iter()
+        .map(|stage| {
+            let status = stage_status(&stage.status);
+            let started_at = from_millis(stage.start_time_millis);
+            let completed_at = match status {
+                BuildStatus::Running | BuildStatus::ActionRequired => None,
+                _ => started_at.map(|start| start + Duration::milliseconds(stage.duration_millis)),
+            };
+            JobInfo {
+                id: stage.id.parse().unwrap_or(0),
+                name: stage.name,
+                status,
+                started_at,
+                completed_at,
+                steps: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+pub struct JenkinsModule;
+
+impl JenkinsModule {
+    pub async fn get_status(api: &JenkinsApi, job_name: &str, branch: Option<&str>) -> Result<Vec<BuildInfo>> {
+        let response = api
+            .get("/api/json")
+            .query(&[("tree", BUILDS_TREE)])
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        let job: JenkinsJob = response.json().await?;
+        Ok(parse_builds(job, job_name, branch))
+    }
+    
+    /// Pipeline stages are shown as jobs. Freestyle jobs have no stages and
+    /// show as a single entry.
+    pub async fn get_jobs(api: &JenkinsApi, build_number: u64) -> Result<Vec<JobInfo>> {
+        let response = api
+            .get(&format!("/{}/wfapi/describe", build_number))
+            .send()
+            .await?;
+        
+        if response.status() == StatusCode::NOT_FOUND {
+            let response = api
+                .get(&format!("/{}/api/json", build_number))
+                .query(&[("tree", "number,result,inProgress,timestamp,duration")])
+                .send()
+                .await?
+                .error_for_status()?;
+            let build: JenkinsBuild = response.json().await?;
+            let started_at = from_millis(build.timestamp);
+            return Ok(vec![JobInfo {
+                id: build.number,
+                name: "build".to_string(),
+                status: build_status(build.result.as_deref(), build.in_progress),
+                started_at,
+                completed_at: if build.in_progress {
+                    None
+                } else {
+                    started_at.map(|start| start + Duration::milliseconds(build.duration))
+                },
+                steps: Vec::new(),
+            }]);
+        }
+        
+        let description: JenkinsRunDescription = response.error_for_status()?.json().await?;
+        Ok(parse_stages(description))
+    }
+    
+    /// Re-running starts a fresh build of the job; dispatch passes `inputs`
+    /// as build parameters.
+    pub async fn run_action(api: &JenkinsApi, action: &BuildAction) -> Result<()> {
+        let request = match action {
+            BuildAction::Rerun(_) => api.post("/build"),
+            BuildAction::RerunFailed(_) => return Err(anyhow!("not supported by Jenkins")),
+            BuildAction::Cancel(number) => api.post(&format!("/{}/stop", number)),
+            BuildAction::Dispatch { inputs, .. } if inputs.is_empty() => api.post("/build"),
+            BuildAction::Dispatch { inputs, .. } => api.post("/buildWithParameters").query(inputs),
+        };
+        
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+    
+    /// Stage logs are only exposed piecemeal as HTML, so every stage opens
+    /// the build's full console output.
+    pub async fn get_job_log(api: &JenkinsApi, build_number: u64) -> Result<String> {
+        let response = api
+            .get(&format!("/{}/consoleText", build_number))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        Ok(response.text().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    const BUILDS: &str = include_str!("../../tests/fixtures/ci/jenkins_builds.json");
+    const STAGES: &str = include_str!("../../tests/fixtures/ci/jenkins_describe.json");
+    
+    #[test]
+    fn builds_fixture() {
+        let builds = parse_builds(serde_json::from_str(BUILDS).unwrap(), "app", None);
+        
+        assert_eq!(builds.iter().map(|b| b.id).collect::<Vec<_>>(), vec![143, 142, 141]);
+        assert!(matches!(builds[0].status, BuildStatus::Running));
+        assert!(matches!(builds[1].status, BuildStatus::Failure));
+        assert!(matches!(builds[2].status, BuildStatus::Success));
+        assert_eq!(builds[1].commit_sha, "e4d2f1a");
+        assert_eq!(builds[1].message, "Handle empty config file");
+        assert_eq!(builds[1].finished_at.unwrap().to_rfc3339(), "2025-03-06T10:04:10+00:00");
+        assert!(builds[0].finished_at.is_none());
+    }
+    
+    #[test]
+    fn builds_are_filtered_by_built_branch() {
+        let builds = parse_builds(serde_json::from_str(BUILDS).unwrap(), "app", Some("main"));
+        assert_eq!(builds.iter().map(|b| b.id).collect::<Vec<_>>(), vec![142, 141]);
+        
+        assert_eq!(local_branch("refs/remotes/origin/feature/x"), "feature/x");
+        assert_eq!(local_branch("origin/main"), "main");
+        assert_eq!(local_branch("refs/heads/main"), "main");
+    }
+    
+    #[test]
+    fn stages_fixture() {
+        let jobs = parse_stages(serde_json::from_str(STAGES).unwrap());
+        
+        assert_eq!(jobs.iter().map(|j| j.name.as_str()).collect::<Vec<_>>(), vec!["Checkout", "Build", "Test"]);
+        assert_eq!(jobs[2].id, 27);
+        assert!(matches!(jobs[2].status, BuildStatus::Failure));
+        assert_eq!(
+            super::super::build::duration(jobs[1].started_at, jobs[1].completed_at),
+            Some(Duration::milliseconds(95_400))
+        );
+    }
+    
+    #[tokio::test]
+    async fn jobs_in_folders_and_parameterized_dispatch() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/job/team/job/app/api/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(BUILDS))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/job/team/job/app/buildWithParameters"))
+            .and(query_param("TARGET", "staging"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = JenkinsApi::new(reqwest::Client::new(), &server.uri(), "team/app", Some("ci"), Some("token"));
+        let builds = JenkinsModule::get_status(&api, "app", None).await.unwrap();
+        assert_eq!(builds.len(), 3);
+        
+        let dispatch = BuildAction::Dispatch {
+            workflow: "app".to_string(),
+            git_ref: "main".to_string(),
+            inputs: [("TARGET".to_string(), "staging".to_string())].into(),
+        };
+        JenkinsModule::run_action(&api, &dispatch).await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn freestyle_jobs_show_as_single_entry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/job/app/7/wfapi/describe"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/job/app/7/api/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"number":7,"result":"FAILURE","inProgress":false,"timestamp":1741255200000,"duration":61000}"#,
+            ))
+            .mount(&server)
+            .await;
+        
+        let api = JenkinsApi::new(reqwest::Client::new(), &server.uri(), "app", None, None);
+        let jobs = JenkinsModule::get_jobs(&api, 7).await.unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert!(matches!(jobs[0].status, BuildStatus::Failure));
+    }
+}
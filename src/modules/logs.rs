@@ -0,0 +1,179 @@
+const ERROR_PATTERNS: [&str; 5] = ["error:", "error[", "##[error]", "FAILED", "panicked at"];
+
+#[derive(Debug, Clone)]
+pub struct LogView {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+    pub query: String,
+    pub searching: bool,
+    pub matches: Vec<usize>,
+    pub error_lines: Vec<usize>,
+    current_match: usize,
+}
+
+impl LogView {
+    /// Builds a viewer for a raw CI log and scrolls to the first line that
+    /// looks like a failure, if any.
+    pub fn new(title: String, raw: &str) -> Self {
+        let lines: Vec<String> = raw.lines().map(|l| strip_timestamp(l).to_string()).collect();
+        let error_lines: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| is_error_line(&strip_ansi(line)))
+            .map(|(i, _)| i)
+            .collect();
+        
+        let mut view = LogView {
+            title,
+            lines,
+            scroll: 0,
+            query: String::new(),
+            searching: false,
+            matches: Vec::new(),
+            error_lines,
+            current_match: 0,
+        };
+        view.jump_to_first_error();
+        view
+    }
+    
+    pub fn jump_to_first_error(&mut self) {
+        if let Some(&line) = self.error_lines.first() {
+            self.scroll = line;
+        }
+    }
+    
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll = (self.scroll + amount).min(self.lines.len().saturating_sub(1));
+    }
+    
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll = self.scroll.saturating_sub(amount);
+    }
+    
+    /// Runs a case-insensitive search over the plain text of every line and
+    /// moves to the first match at or below the current position.
+    pub fn search(&mut self) {
+        let needle = self.query.to_lowercase();
+        self.matches = if needle.is_empty() {
+            Vec::new()
+        } else {
+            self.lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| strip_ansi(line).to_lowercase().contains(&needle))
+                .map(|(i, _)| i)
+                .collect()
+        };
+        
+        self.current_match = self
+            .matches
+            .iter()
+            .position(|&line| line >= self.scroll)
+            .unwrap_or(0);
+        if let Some(&line) = self.matches.get(self.current_match) {
+            self.scroll = line;
+        }
+    }
+    
+    pub fn next_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + 1) % self.matches.len();
+            self.scroll = self.matches[self.current_match];
+        }
+    }
+    
+    pub fn prev_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + self.matches.len() - 1) % self.matches.len();
+            self.scroll = self.matches[self.current_match];
+        }
+    }
+}
+
+pub fn is_error_line(line: &str) -> bool {
+    ERROR_PATTERNS.iter().any(|pattern| line.contains(pattern))
+}
+
+/// Removes CSI escape sequences (colors, cursor movement) so lines can be
+/// searched as plain text.
+pub fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// GitHub prefixes every log line with an RFC 3339 timestamp such as
+/// `2024-05-01T12:00:00.1234567Z `; it only adds noise in a narrow pane.
+fn strip_timestamp(line: &str) -> &str {
+    match line.split_once(' ') {
+        Some((stamp, rest)) if stamp.len() >= 20 && stamp.ends_with('Z') && stamp.as_bytes()[10] == b'T' => rest,
+        _ => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn strip_ansi_removes_color_codes() {
+        assert_eq!(strip_ansi("\u{1b}[31merror\u{1b}[0m: boom"), "error: boom");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+    
+    #[test]
+    fn strip_timestamp_only_removes_rfc3339_prefix() {
+        assert_eq!(strip_timestamp("2024-05-01T12:00:00.1234567Z Compiling foo"), "Compiling foo");
+        assert_eq!(strip_timestamp("Compiling foo v0.1.0"), "Compiling foo v0.1.0");
+    }
+    
+    #[test]
+    fn new_scrolls_to_first_error() {
+        let raw = "2024-05-01T12:00:00.1234567Z Compiling foo\n\
+                   \u{1b}[1;31merror\u{1b}[0m[E0308]: mismatched types\n\
+                   thread 'main' panicked at src/main.rs:1:1";
+        let view = LogView::new("build".to_string(), raw);
+        
+        assert_eq!(view.lines[0], "Compiling foo");
+        assert_eq!(view.error_lines, vec![1, 2]);
+        assert_eq!(view.scroll, 1);
+    }
+    
+    #[test]
+    fn search_is_case_insensitive_and_wraps() {
+        let mut view = LogView::new("build".to_string(), "Test one\nother\ntest two\ntest three");
+        view.scroll = 1;
+        view.query = "TEST".to_string();
+        view.search();
+        
+        assert_eq!(view.matches, vec![0, 2, 3]);
+        assert_eq!(view.scroll, 2);
+        view.next_match();
+        assert_eq!(view.scroll, 3);
+        view.next_match();
+        assert_eq!(view.scroll, 0);
+        view.prev_match();
+        assert_eq!(view.scroll, 3);
+        
+        view.query.clear();
+        view.search();
+        assert!(view.matches.is_empty());
+        view.next_match();
+        assert_eq!(view.scroll, 3);
+    }
+}
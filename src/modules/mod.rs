@@ -1,5 +1,18 @@
+pub mod api;
+pub mod artifacts;
 pub mod build;
+pub mod cargo;
+pub mod ci;
 pub mod coverage;
 pub mod git;
+pub mod gitea;
+pub mod insights;
+pub mod jenkins;
+pub mod logs;
+pub mod notify;
+pub mod pulls;
+pub mod remote;
 pub mod system;
+pub mod test_results;
 pub mod timer;
+pub mod woodpecker;
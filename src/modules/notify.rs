@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::Stdio;
+use tokio::process::Command;
+
+use super::build::{BuildInfo, BuildStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyBackend {
+    Off,
+    /// The terminal bell; most terminals flash or mark the tab.
+    #[default]
+    Bell,
+    /// `OSC 9`, understood by iTerm2, Windows Terminal, kitty and others.
+    Osc9,
+    /// `OSC 777`, understood by urxvt, foot and VTE-based terminals.
+    Osc777,
+    /// Runs `notify_command` through `sh -c`.
+    Command,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub run_id: u64,
+    pub status: BuildStatus,
+    pub title: String,
+    pub body: String,
+}
+
+/// Remembers the last status seen for each run so that only transitions
+/// are reported, and which results were already announced so a run is
+/// reported once per attempt.
+#[derive(Debug, Default)]
+pub struct Notifier {
+    last_seen: HashMap<u64, BuildStatus>,
+    notified: HashSet<(u64, Option<DateTime<Utc>>)>,
+}
+
+impl Notifier {
+    /// Compares a fresh fetch with the previous one. Runs on other branches
+    /// are tracked but never reported.
+    pub fn observe(&mut self, builds: &[BuildInfo], branch: &str) -> Vec<Notification> {
+        let mut notifications = Vec::new();
+        for build in builds {
+            let previous = self.last_seen.insert(build.id, build.status);
+            let finished = matches!(
+                build.status,
+                BuildStatus::Success | BuildStatus::Failure | BuildStatus::TimedOut
+            );
+            if !finished || !previous.is_some_and(BuildStatus::is_active) || build.branch != branch {
+                continue;
+            }
+            if !self.notified.insert((build.id, build.started_at)) {
+                continue;
+            }
+            
+            let outcome = match build.status {
+                BuildStatus::Success => "passed",
+                BuildStatus::TimedOut => "timed out",
+                _ => "failed",
+            };
+            notifications.push(Notification {
+                run_id: build.id,
+                status: build.status,
+                title: format!("{} {} on {}", build.name, outcome, build.branch),
+                body: format!("#{} {} {}", build.id, build.commit_sha, build.message),
+            });
+        }
+        notifications
+    }
+}
+
+/// Commit messages end up in escape sequences and environment variables,
+/// so control characters are dropped.
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+fn escape_sequence(backend: NotifyBackend, notification: &Notification) -> Option<String> {
+    let title = sanitize(&notification.title);
+    let body = sanitize(&notification.body);
+    match backend {
+        NotifyBackend::Bell => Some("\x07".to_string()),
+        NotifyBackend::Osc9 => Some(format!("\x1b]9;{}: {}\x07", title, body)),
+        NotifyBackend::Osc777 => Some(format!("\x1b]777;notify;{};{}\x07", title, body)),
+        NotifyBackend::Off | NotifyBackend::Command => None,
+    }
+}
+
+/// Delivers a notification. Escape sequences go straight to the terminal;
+/// the command gets the details in `DEVDASH_*` environment variables and is
+/// not waited for.
+pub fn send(backend: NotifyBackend, command: Option<&str>, notification: &Notification) -> std::io::Result<()> {
+    if let Some(sequence) = escape_sequence(backend, notification) {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(sequence.as_bytes())?;
+        return stdout.flush();
+    }
+    
+    if let (NotifyBackend::Command, Some(command)) = (backend, command) {
+        Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("DEVDASH_TITLE", sanitize(&notification.title))
+            .env("DEVDASH_BODY", sanitize(&notification.body))
+            .env("DEVDASH_STATUS", format!("{:?}", notification.status).to_lowercase())
+            .env("DEVDASH_RUN_ID", notification.run_id.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn run(id: u64, status: BuildStatus, branch: &str) -> BuildInfo {
+        BuildInfo {
+            id,
+            status,
+            name: "CI".to_string(),
+            branch: branch.to_string(),
+            started_at: DateTime::from_timestamp(1_700_000_000 + id as i64, 0),
+            finished_at: None,
+            commit_sha: "abc1234".to_string(),
+            message: "Fix parser".to_string(),
+        }
+    }
+    
+    #[test]
+    fn only_finished_transitions_on_our_branch_are_reported_once() {
+        let mut notifier = Notifier::default();
+        
+        let first = vec![
+            run(3, BuildStatus::Running, "main"),
+            run(2, BuildStatus::Running, "feature"),
+            run(1, BuildStatus::Failure, "main"),
+        ];
+        assert!(notifier.observe(&first, "main").is_empty());
+        
+        let second = vec![
+            run(3, BuildStatus::Failure, "main"),
+            run(2, BuildStatus::Success, "feature"),
+            run(1, BuildStatus::Failure, "main"),
+        ];
+        let notifications = notifier.observe(&second, "main");
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].run_id, 3);
+        assert_eq!(notifications[0].title, "CI failed on main");
+        assert_eq!(notifications[0].body, "#3 abc1234 Fix parser");
+        
+        // A stale response flipping the run back and forth is not news.
+        notifier.observe(&first, "main");
+        assert!(notifier.observe(&second, "main").is_empty());
+        
+        // A re-run is a new attempt with a new start time.
+        let mut rerun = run(3, BuildStatus::Running, "main");
+        rerun.started_at = DateTime::from_timestamp(1_800_000_000, 0);
+        notifier.observe(&[rerun.clone()], "main");
+        rerun.status = BuildStatus::Success;
+        assert_eq!(notifier.observe(&[rerun], "main").len(), 1);
+    }
+    
+    #[test]
+    fn escape_sequences_drop_control_characters() {
+        let notification = Notification {
+            run_id: 3,
+            status: BuildStatus::Failure,
+            title: "CI failed on main".to_string(),
+            body: "#3 \x1b]0;pwned\x07".to_string(),
+        };
+        
+        assert_eq!(
+            escape_sequence(NotifyBackend::Osc9, &notification).unwrap(),
+            "\x1b]9;CI failed on main: #3 ]0;pwned\x07"
+        );
+        assert_eq!(
+            escape_sequence(NotifyBackend::Osc777, &notification).unwrap(),
+            "\x1b]777;notify;CI failed on main;#3 ]0;pwned\x07"
+        );
+        assert!(escape_sequence(NotifyBackend::Command, &notification).is_none());
+    }
+}
@@ -0,0 +1,455 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+
+use super::api::{GitHubApi, GitLabApi};
+use super::build::{self, BuildStatus};
+
+#[derive(Debug, Clone)]
+pub struct PullRequestInfo {
+    pub number: u64,
+    pub title: String,
+    pub author: String,
+    pub branch: String,
+    pub draft: bool,
+    pub review_state: String,
+    pub checks: BuildStatus,
+    pub mergeable: Option<bool>,
+    pub labels: Vec<String>,
+    pub review_requested: bool,
+}
+
+const GITHUB_PULLS_QUERY: &str = r#"
+query($owner: String!, $name: String!) {
+  viewer { login }
+  repository(owner: $owner, name: $name) {
+    pullRequests(states: OPEN, first: 30, orderBy: {field: UPDATED_AT, direction: DESC}) {
+      nodes {
+        number
+        title
+        isDraft
+        headRefName
+        mergeable
+        reviewDecision
+        author { login }
+        labels(first: 10) { nodes { name } }
+        reviewRequests(first: 20) { nodes { requestedReviewer { ... on User { login } } } }
+        commits(last: 1) { nodes { commit { statusCheckRollup { state } } } }
+      }
+    }
+  }
+}
+"#;
+
+pub struct PullsModule;
+
+impl PullsModule {
+    pub async fn get_github_pulls(
+        api: &GitHubApi,
+        repo: &str,
+        current_branch: &str,
+    ) -> Result<Vec<PullRequestInfo>> {
+        let (owner, name) = repo
+            .split_once('/')
+            .ok_or_else(|| anyhow!("github_repo must be in owner/repo form"))?;
+        
+        let response = api
+            .graphql()
+            .json(&json!({
+                "query": GITHUB_PULLS_QUERY,
+                "variables": { "owner": owner, "name": name },
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        let body: Value = response.json().await?;
+        if let Some(message) = body["errors"][0]["message"].as_str() {
+            return Err(anyhow!("GitHub GraphQL error: {}", message));
+        }
+        
+        let viewer = body["data"]["viewer"]["login"].as_str().unwrap_or("");
+        let empty = Vec::new();
+        let nodes = body["data"]["repository"]["pullRequests"]["nodes"]
+            .as_array()
+            .unwrap_or(&empty);
+        
+        let pulls = nodes
+            .iter()
+            .map(|pr| {
+                let review_requested = pr["reviewRequests"]["nodes"]
+                    .as_array()
+                    .map(|requests| {
+                        requests
+                            .iter()
+                            .any(|r| r["requestedReviewer"]["login"].as_str() == Some(viewer))
+                    })
+                    .unwrap_or(false);
+                
+                PullRequestInfo {
+                    number: pr["number"].as_u64().unwrap_or(0),
+                    title: pr["title"].as_str().unwrap_or("").to_string(),
+                    author: pr["author"]["login"].as_str().unwrap_or("ghost").to_string(),
+                    branch: pr["headRefName"].as_str().unwrap_or("").to_string(),
+                    draft: pr["isDraft"].as_bool().unwrap_or(false),
+                    review_state: match pr["reviewDecision"].as_str() {
+                        Some("APPROVED") => "approved",
+                        Some("CHANGES_REQUESTED") => "changes requested",
+                        Some("REVIEW_REQUIRED") => "review required",
+                        _ => "no review",
+                    }
+                    .to_string(),
+                    checks: match pr["commits"]["nodes"][0]["commit"]["statusCheckRollup"]["state"].as_str() {
+                        Some("SUCCESS") => BuildStatus::Success,
+                        Some("FAILURE") | Some("ERROR") => BuildStatus::Failure,
+                        Some("PENDING") => BuildStatus::Running,
+                        Some("EXPECTED") => BuildStatus::Queued,
+                        _ => BuildStatus::Unknown,
+                    },
+                    mergeable: match pr["mergeable"].as_str() {
+                        Some("MERGEABLE") => Some(true),
+                        Some("CONFLICTING") => Some(false),
+                        _ => None,
+                    },
+                    labels: pr["labels"]["nodes"]
+                        .as_array()
+                        .map(|labels| {
+                            labels
+                                .iter()
+                                .filter_map(|l| l["name"].as_str().map(|s| s.to_string()))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    review_requested,
+                }
+            })
+            .collect();
+        
+        Ok(Self::sort_for_branch(pulls, current_branch))
+    }
+    
+    pub async fn get_gitlab_merge_requests(
+        api: &GitLabApi,
+        project_id: &str,
+        current_branch: &str,
+    ) -> Result<Vec<PullRequestInfo>> {
+        let user: Value = api
+            .get("/user")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let username = user["username"].as_str().unwrap_or("");
+        
+        let merge_requests: Vec<Value> = api
+            .get(&format!("/projects/{}/merge_requests?state=opened&per_page=30", project_id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        
+        // The list leaves out the pipeline and the approvers, so both are
+        // looked up per merge request, concurrently.
+        let details =
+            futures::future::join_all(merge_requests.iter().map(|mr| Self::gitlab_details(api, project_id, mr))).await;
+        
+        let mut pulls = Vec::with_capacity(merge_requests.len());
+        for (mr, (checks, approved)) in merge_requests.iter().zip(details) {
+            let detailed = mr["detailed_merge_status"].as_str().unwrap_or("");
+            pulls.push(PullRequestInfo {
+                number: mr["iid"].as_u64().unwrap_or(0),
+                title: mr["title"].as_str().unwrap_or("").to_string(),
+                author: mr["author"]["username"].as_str().unwrap_or("").to_string(),
+                branch: mr["source_branch"].as_str().unwrap_or("").to_string(),
+                draft: mr["draft"].as_bool().unwrap_or(false),
+                review_state: match detailed {
+                    "not_approved" => "review required",
+                    "requested_changes" => "changes requested",
+                    _ if approved => "approved",
+                    _ => "no review",
+                }
+                .to_string(),
+                checks,
+                mergeable: match mr["has_conflicts"].as_bool() {
+                    Some(true) => Some(false),
+                    Some(false) => Some(detailed == "mergeable"),
+                    None => None,
+                },
+                labels: mr["labels"]
+                    .as_array()
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|l| l.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                review_requested: mr["reviewers"]
+                    .as_array()
+                    .map(|reviewers| reviewers.iter().any(|r| r["username"].as_str() == Some(username)))
+                    .unwrap_or(false),
+            });
+        }
+        
+        Ok(Self::sort_for_branch(pulls, current_branch))
+    }
+    
+    /// Status of the merge request's head pipeline (`Unknown` without one)
+    /// and whether anyone approved it. `detailed_merge_status` cannot tell a
+    /// failed pipeline from a running one, and "mergeable" only says the
+    /// approval rules are met, which is also true when there are none.
+    async fn gitlab_details(api: &GitLabApi, project_id: &str, mr: &Value) -> (BuildStatus, bool) {
+        let iid = mr["iid"].as_u64().unwrap_or(0);
+        let detail: Option<Value> = match api
+            .get(&format!("/projects/{}/merge_requests/{}", project_id, iid))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+        {
+            Ok(response) => response.json().await.ok(),
+            Err(_) => None,
+        };
+        let checks = detail
+            .as_ref()
+            .and_then(|detail| detail["head_pipeline"]["status"].as_str())
+            .map(build::gitlab_status)
+            .unwrap_or(BuildStatus::Unknown);
+        
+        let approved = mr["detailed_merge_status"].as_str() == Some("mergeable")
+            && Self::gitlab_approved(api, project_id, iid).await;
+        (checks, approved)
+    }
+    
+    async fn gitlab_approved(api: &GitLabApi, project_id: &str, iid: u64) -> bool {
+        let Ok(response) = api
+            .get(&format!("/projects/{}/merge_requests/{}/approvals", project_id, iid))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+        else {
+            return false;
+        };
+        response
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|approvals| approvals["approved_by"].as_array().map(|by| !by.is_empty()))
+            .unwrap_or(false)
+    }
+    
+    /// Pins the pull request for the checked-out branch first, then the ones
+    /// waiting on the current user's review; the API order is kept otherwise.
+    fn sort_for_branch(mut pulls: Vec<PullRequestInfo>, current_branch: &str) -> Vec<PullRequestInfo> {
+        pulls.sort_by_key(|pr| (pr.branch != current_branch, !pr.review_requested));
+        pulls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    fn github_pr(number: u64, branch: &str, requested: &[&str]) -> Value {
+        json!({
+            "number": number,
+            "title": format!("PR {}", number),
+            "isDraft": false,
+            "headRefName": branch,
+            "mergeable": "MERGEABLE",
+            "reviewDecision": "REVIEW_REQUIRED",
+            "author": { "login": "alice" },
+            "labels": { "nodes": [{ "name": "bug" }] },
+            "reviewRequests": {
+                "nodes": requested
+                    .iter()
+                    .map(|login| json!({ "requestedReviewer": { "login": login } }))
+                    .collect::<Vec<_>>()
+            },
+            "commits": { "nodes": [{ "commit": { "statusCheckRollup": { "state": "FAILURE" } } }] }
+        })
+    }
+    
+    #[tokio::test]
+    async fn github_enterprise_pulls_use_api_graphql_and_pin_current_branch() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/graphql"))
+            .and(header("Authorization", "Bearer gh-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {
+                    "viewer": { "login": "me" },
+                    "repository": { "pullRequests": { "nodes": [
+                        github_pr(1, "other", &[]),
+                        github_pr(2, "review-me", &["me"]),
+                        github_pr(3, "my-branch", &[]),
+                    ] } }
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = GitHubApi::new(reqwest::Client::new(), &format!("{}/api/v3", server.uri()), "gh-token");
+        let pulls = PullsModule::get_github_pulls(&api, "acme/app", "my-branch").await.unwrap();
+        
+        let numbers: Vec<u64> = pulls.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, vec![3, 2, 1]);
+        assert!(pulls[1].review_requested);
+        assert_eq!(pulls[0].labels, vec!["bug".to_string()]);
+        assert_eq!(pulls[0].mergeable, Some(true));
+        assert!(matches!(pulls[0].checks, BuildStatus::Failure));
+        assert_eq!(pulls[0].review_state, "review required");
+    }
+    
+    #[tokio::test]
+    async fn github_graphql_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "errors": [{ "message": "Could not resolve to a Repository" }]
+            })))
+            .mount(&server)
+            .await;
+        
+        let api = GitHubApi::new(reqwest::Client::new(), &server.uri(), "gh-token");
+        let err = PullsModule::get_github_pulls(&api, "acme/missing", "main").await.unwrap_err();
+        assert!(err.to_string().contains("Could not resolve"));
+    }
+    
+    #[tokio::test]
+    async fn gitlab_merge_requests_flag_requested_reviews() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/user"))
+            .and(header("PRIVATE-TOKEN", "gl-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "username": "me" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests"))
+            .and(query_param("state", "opened"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "iid": 5,
+                    "title": "Add feature",
+                    "author": { "username": "bob" },
+                    "source_branch": "feature",
+                    "draft": true,
+                    "detailed_merge_status": "ci_still_running",
+                    "has_conflicts": false,
+                    "labels": ["backend"],
+                    "reviewers": [{ "username": "me" }]
+                },
+                {
+                    "iid": 4,
+                    "title": "Conflicting",
+                    "author": { "username": "carol" },
+                    "source_branch": "old",
+                    "draft": false,
+                    "detailed_merge_status": "broken_status",
+                    "has_conflicts": true,
+                    "labels": [],
+                    "reviewers": []
+                }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests/5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "iid": 5,
+                "head_pipeline": { "id": 900, "status": "running" }
+            })))
+            .mount(&server)
+            .await;
+        
+        let api = GitLabApi::new(reqwest::Client::new(), &format!("{}/api/v4", server.uri()), "gl-token");
+        let pulls = PullsModule::get_gitlab_merge_requests(&api, "42", "main").await.unwrap();
+        
+        assert_eq!(pulls.len(), 2);
+        assert_eq!(pulls[0].number, 5);
+        assert!(pulls[0].review_requested);
+        assert!(pulls[0].draft);
+        assert!(matches!(pulls[0].checks, BuildStatus::Running));
+        assert_eq!(pulls[1].mergeable, Some(false));
+        assert!(matches!(pulls[1].checks, BuildStatus::Unknown));
+    }
+    
+    #[tokio::test]
+    async fn gitlab_review_and_checks_come_from_approvals_and_pipeline() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/user"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "username": "me" })))
+            .mount(&server)
+            .await;
+        let mr = |iid: u64, status: &str| {
+            json!({
+                "iid": iid,
+                "title": format!("MR {}", iid),
+                "author": { "username": "bob" },
+                "source_branch": format!("branch-{}", iid),
+                "detailed_merge_status": status,
+                "has_conflicts": false,
+                "labels": [],
+                "reviewers": []
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                mr(1, "mergeable"),
+                mr(2, "mergeable"),
+                mr(3, "ci_must_pass"),
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests/1/approvals"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "approved": true,
+                "approved_by": [{ "user": { "username": "carol" } }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests/2/approvals"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "approved": true, "approved_by": [] })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "iid": 1,
+                "head_pipeline": { "id": 901, "status": "success" }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests/2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "iid": 2, "head_pipeline": null })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v4/projects/42/merge_requests/3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "iid": 3,
+                "head_pipeline": { "id": 902, "status": "failed" }
+            })))
+            .mount(&server)
+            .await;
+        
+        let api = GitLabApi::new(reqwest::Client::new(), &format!("{}/api/v4", server.uri()), "gl-token");
+        let pulls = PullsModule::get_gitlab_merge_requests(&api, "42", "main").await.unwrap();
+        
+        let states: Vec<&str> = pulls.iter().map(|pr| pr.review_state.as_str()).collect();
+        assert_eq!(states, vec!["approved", "no review", "no review"]);
+        assert!(matches!(pulls[0].checks, BuildStatus::Success));
+        assert!(matches!(pulls[1].checks, BuildStatus::Unknown));
+        assert!(matches!(pulls[2].checks, BuildStatus::Failure));
+    }
+}
@@ -0,0 +1,195 @@
+use git2::Repository;
+
+use crate::config::{Config, GiteaConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+/// Where the `origin` remote points: the hosting provider, its host name and
+/// the repository path (`owner/repo`, or `group/subgroup/project` on GitLab).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteInfo {
+    pub provider: Option<Provider>,
+    pub host: String,
+    pub path: String,
+}
+
+impl RemoteInfo {
+    pub fn detect(repo_path: &str) -> Option<Self> {
+        let repo = Repository::open(repo_path).ok()?;
+        let remote = repo.find_remote("origin").ok()?;
+        Self::parse(remote.url()?)
+    }
+    
+    /// Accepts scp-style SSH (`git@host:owner/repo.git`) as well as
+    /// `ssh://`, `git://`, `http://` and `https://` URLs.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let (authority, path) = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?,
+            None => url.split_once(':')?,
+        };
+        
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?.to_lowercase();
+        let path = path.trim_matches('/').trim_end_matches(".git").to_string();
+        if host.is_empty() || !path.contains('/') {
+            return None;
+        }
+        
+        let provider = if host.contains("github") {
+            Some(Provider::GitHub)
+        } else if host.contains("gitlab") {
+            Some(Provider::GitLab)
+        } else if host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org" {
+            Some(Provider::Gitea)
+        } else {
+            None
+        };
+        
+        Some(RemoteInfo { provider, host, path })
+    }
+    
+    pub fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+    
+    /// GitLab accepts a URL-encoded project path wherever a numeric id is
+    /// expected.
+    pub fn gitlab_project_id(&self) -> String {
+        self.path.replace('/', "%2F")
+    }
+    
+    /// Fills in whatever the config leaves unset. Hosts that do not name
+    /// their provider are matched against the configured API URLs.
+    pub fn apply_to(&self, config: &mut Config) {
+        let provider = self.provider.or_else(|| {
+            if config.github_api_url.contains(&self.host) {
+                Some(Provider::GitHub)
+            } else if config.gitlab_api_url.contains(&self.host) {
+                Some(Provider::GitLab)
+            } else if config.gitea.as_ref().is_some_and(|gitea| gitea.url.contains(&self.host)) {
+                Some(Provider::Gitea)
+            } else {
+                None
+            }
+        });
+        
+        match provider {
+            Some(Provider::GitHub) => {
+                if config.github_repo.is_none() && config.gitlab_project_id.is_none() {
+                    config.github_repo = Some(self.path.clone());
+                }
+                if self.host != "github.com" && config.github_api_url == Config::default().github_api_url {
+                    config.github_api_url = format!("https://{}/api/v3", self.host);
+                }
+            }
+            Some(Provider::GitLab) => {
+                if config.gitlab_project_id.is_none() && config.github_repo.is_none() {
+                    config.gitlab_project_id = Some(self.gitlab_project_id());
+                }
+                if self.host != "gitlab.com" && config.gitlab_api_url == Config::default().gitlab_api_url {
+                    config.gitlab_api_url = format!("https://{}/api/v4", self.host);
+                }
+            }
+            Some(Provider::Gitea) => {
+                let gitea = config.gitea.get_or_insert_with(|| GiteaConfig {
+                    url: format!("https://{}/api/v1", self.host),
+                    token: None,
+                    repo: None,
+                });
+                if gitea.repo.is_none() {
+                    gitea.repo = Some(self.path.clone());
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_ssh_and_https_remotes() {
+        let cases = [
+            ("git@github.com:acme/app.git", "github.com", "acme/app"),
+            ("https://github.com/acme/app", "github.com", "acme/app"),
+            ("https://token@github.com/acme/app.git/", "github.com", "acme/app"),
+            ("ssh://git@gitlab.example.com:2222/group/sub/app.git", "gitlab.example.com", "group/sub/app"),
+            ("git://git.example.org/team/app.git", "git.example.org", "team/app"),
+        ];
+        for (url, host, path) in cases {
+            let remote = RemoteInfo::parse(url).unwrap();
+            assert_eq!(remote.host, host, "{}", url);
+            assert_eq!(remote.path, path, "{}", url);
+        }
+        
+        assert_eq!(RemoteInfo::parse("/srv/git/app.git"), None);
+        assert_eq!(RemoteInfo::parse("https://github.com/acme"), None);
+    }
+    
+    #[test]
+    fn github_remote_fills_repo_and_enterprise_url() {
+        let mut config = Config::default();
+        RemoteInfo::parse("git@github.com:acme/app.git").unwrap().apply_to(&mut config);
+        assert_eq!(config.github_repo.as_deref(), Some("acme/app"));
+        assert_eq!(config.github_api_url, "https://api.github.com");
+        
+        let mut config = Config::default();
+        RemoteInfo::parse("https://github.acme.corp/team/app").unwrap().apply_to(&mut config);
+        assert_eq!(config.github_api_url, "https://github.acme.corp/api/v3");
+    }
+    
+    #[test]
+    fn gitlab_remote_uses_encoded_project_path() {
+        let mut config = Config::default();
+        RemoteInfo::parse("git@gitlab.com:group/sub/app.git").unwrap().apply_to(&mut config);
+        assert_eq!(config.gitlab_project_id.as_deref(), Some("group%2Fsub%2Fapp"));
+        assert_eq!(config.github_repo, None);
+    }
+    
+    #[test]
+    fn forgejo_remote_configures_gitea_provider() {
+        let mut config = Config::default();
+        RemoteInfo::parse("https://codeberg.org/acme/app.git").unwrap().apply_to(&mut config);
+        let gitea = config.gitea.unwrap();
+        assert_eq!(gitea.url, "https://codeberg.org/api/v1");
+        assert_eq!(gitea.repo.as_deref(), Some("acme/app"));
+        assert_eq!(config.github_repo, None);
+    }
+    
+    #[test]
+    fn unnamed_host_is_matched_against_configured_api_url() {
+        let mut config = Config {
+            gitlab_api_url: "https://code.acme.corp/api/v4".to_string(),
+            ..Config::default()
+        };
+        RemoteInfo::parse("git@code.acme.corp:platform/app.git").unwrap().apply_to(&mut config);
+        assert_eq!(config.gitlab_project_id.as_deref(), Some("platform%2Fapp"));
+    }
+    
+    #[test]
+    fn config_values_override_detection() {
+        let mut config = Config {
+            github_repo: Some("fork/app".to_string()),
+            github_api_url: "https://proxy.acme.corp/github".to_string(),
+            ..Config::default()
+        };
+        RemoteInfo::parse("git@github.acme.corp:acme/app.git").unwrap().apply_to(&mut config);
+        assert_eq!(config.github_repo.as_deref(), Some("fork/app"));
+        assert_eq!(config.github_api_url, "https://proxy.acme.corp/github");
+        
+        let mut config = Config {
+            gitlab_project_id: Some("12345".to_string()),
+            ..Config::default()
+        };
+        RemoteInfo::parse("git@github.com:acme/app.git").unwrap().apply_to(&mut config);
+        assert_eq!(config.github_repo, None);
+    }
+}
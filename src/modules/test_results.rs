@@ -0,0 +1,430 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+const RESULTS_FILE: &str = "test_results.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestHarness {
+    #[default]
+    Cargo,
+    Nextest,
+}
+
+impl TestHarness {
+    pub fn label(self) -> &'static str {
+        match self {
+            TestHarness::Cargo => "cargo test",
+            TestHarness::Nextest => "cargo nextest",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+    pub outcome: TestOutcome,
+    #[serde(default)]
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestReport {
+    pub harness: TestHarness,
+    pub finished_at: DateTime<Utc>,
+    pub cases: Vec<TestCase>,
+    /// Compiler errors or other output when the suite did not run at all.
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Set for results that were not run locally, e.g. the name of the CI
+    /// artifact they came from.
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+impl TestReport {
+    pub fn load() -> Option<Self> {
+        if !Path::new(RESULTS_FILE).exists() {
+            return None;
+        }
+        let content = fs::read_to_string(RESULTS_FILE).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+    
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(RESULTS_FILE, content)?;
+        Ok(())
+    }
+    
+    pub fn count(&self, outcome: TestOutcome) -> usize {
+        self.cases.iter().filter(|case| case.outcome == outcome).count()
+    }
+    
+    pub fn failures(&self) -> Vec<&TestCase> {
+        self.cases
+            .iter()
+            .filter(|case| case.outcome == TestOutcome::Failed)
+            .collect()
+    }
+    
+    /// A failure is new unless the test also failed last time. Tests that
+    /// did not exist before count as new.
+    pub fn is_newly_failing(&self, name: &str, previous: Option<&TestReport>) -> bool {
+        let failed_before = previous
+            .map(|report| {
+                report
+                    .cases
+                    .iter()
+                    .any(|case| case.name == name && case.outcome == TestOutcome::Failed)
+            })
+            .unwrap_or(false);
+        !failed_before
+    }
+}
+
+/// The report new failures are measured against once `replaced` is no
+/// longer shown: the last local run. Results from CI are shown but never
+/// become the baseline, so the previous one is kept.
+pub fn next_baseline(replaced: Option<TestReport>, previous: Option<TestReport>) -> Option<TestReport> {
+    match replaced {
+        Some(report) if report.source.is_none() => Some(report),
+        _ => previous,
+    }
+}
+
+/// Parses libtest's default human-readable output, the only stable format
+/// `cargo test` offers. Captured output of failing tests is taken from the
+/// `---- name stdout ----` blocks in the failures section.
+pub fn parse_libtest_output(output: &str) -> Vec<TestCase> {
+    let mut cases: Vec<TestCase> = Vec::new();
+    let mut current: Option<usize> = None;
+    
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("---- ").and_then(|l| l.strip_suffix(" ----")) {
+            let name = header
+                .strip_suffix(" stdout")
+                .or_else(|| header.strip_suffix(" stderr"))
+                .unwrap_or(header);
+            current = cases.iter().position(|case| case.name == name);
+            continue;
+        }
+        if line == "failures:" || line.starts_with("test result:") {
+            current = None;
+            continue;
+        }
+        if let Some(index) = current {
+            if line.starts_with("note: run with `RUST_BACKTRACE") {
+                continue;
+            }
+            let case = &mut cases[index];
+            case.output.push_str(line);
+            case.output.push('\n');
+            continue;
+        }
+        
+        let Some((name, result)) = line.strip_prefix("test ").and_then(|l| l.rsplit_once(" ... ")) else {
+            continue;
+        };
+        let outcome = match result {
+            "ok" => TestOutcome::Passed,
+            "FAILED" => TestOutcome::Failed,
+            r if r.starts_with("ignored") => TestOutcome::Ignored,
+            _ => continue,
+        };
+        cases.push(TestCase {
+            name: name.to_string(),
+            outcome,
+            output: String::new(),
+        });
+    }
+    
+    for case in &mut cases {
+        case.output = case.output.trim_end().to_string();
+    }
+    cases
+}
+
+#[derive(Deserialize)]
+struct LibtestEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    event: String,
+    name: Option<String>,
+    stdout: Option<String>,
+}
+
+/// Parses libtest's JSON event stream, as emitted by nextest's
+/// `--message-format libtest-json`. Nextest prefixes names with the test
+/// binary (`crate::bin/app$module::test`), which is dropped here.
+pub fn parse_libtest_json(output: &str) -> Vec<TestCase> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<LibtestEvent>(line).ok())
+        .filter(|event| event.kind == "test")
+        .filter_map(|event| {
+            let outcome = match event.event.as_str() {
+                "ok" => TestOutcome::Passed,
+                "failed" | "timeout" => TestOutcome::Failed,
+                "ignored" => TestOutcome::Ignored,
+                _ => return None,
+            };
+            let name = event.name?;
+            let name = name.rsplit_once('$').map_or(name.as_str(), |(_, test)| test).to_string();
+            Some(TestCase {
+                name,
+                outcome,
+                output: event.stdout.unwrap_or_default().trim_end().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parses a JUnit XML report, as written by nextest, pytest, Maven and
+/// most CI test reporters. Test names are prefixed with their class name
+/// when there is one.
+pub fn parse_junit(content: &str) -> anyhow::Result<Vec<TestCase>> {
+    let document = roxmltree::Document::parse(content)?;
+    let root = document.root_element();
+    if !root.has_tag_name("testsuites") && !root.has_tag_name("testsuite") {
+        return Err(anyhow::anyhow!("not a JUnit report"));
+    }
+    
+    let cases = root
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+        .map(|case| {
+            let name = case.attribute("name").unwrap_or("unnamed");
+            let name = match case.attribute("classname").filter(|class| !class.is_empty()) {
+                Some(class) => format!("{}::{}", class, name),
+                None => name.to_string(),
+            };
+            let child = |tag: &str| case.children().find(|node| node.has_tag_name(tag));
+            let failure = child("failure").or_else(|| child("error"));
+            let outcome = match (failure, child("skipped")) {
+                (Some(_), _) => TestOutcome::Failed,
+                (None, Some(_)) => TestOutcome::Ignored,
+                (None, None) => TestOutcome::Passed,
+            };
+            let output = failure
+                .map(|node| {
+                    let message = node.attribute("message").unwrap_or("");
+                    let text = node.text().unwrap_or("").trim();
+                    let stdout = child("system-out").and_then(|n| n.text()).unwrap_or("").trim();
+                    [message, text, stdout]
+                        .into_iter()
+                        .filter(|part| !part.is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            TestCase { name, outcome, output }
+        })
+        .collect();
+    Ok(cases)
+}
+
+/// The test suite running in the background; the report arrives through a
+/// oneshot channel once the process exits.
+pub struct TestRun {
+    pub harness: TestHarness,
+    pub started_at: DateTime<Utc>,
+    result: oneshot::Receiver<TestReport>,
+    task: JoinHandle<()>,
+}
+
+impl TestRun {
+    pub fn spawn(harness: TestHarness, repo_path: &str) -> Self {
+        let (sender, result) = oneshot::channel();
+        let repo_path = repo_path.to_string();
+        let task = tokio::spawn(async move {
+            let report = Self::run(harness, &repo_path).await.unwrap_or_else(|e| TestReport {
+                harness,
+                finished_at: Utc::now(),
+                cases: Vec::new(),
+                error: Some(format!("failed to run {}: {}", harness.label(), e)),
+                source: None,
+            });
+            let _ = sender.send(report);
+        });
+        
+        TestRun {
+            harness,
+            started_at: Utc::now(),
+            result,
+            task,
+        }
+    }
+    
+    async fn run(harness: TestHarness, repo_path: &str) -> std::io::Result<TestReport> {
+        let mut command = Command::new("cargo");
+        match harness {
+            TestHarness::Cargo => {
+                command.args(["test", "--no-fail-fast"]);
+            }
+            TestHarness::Nextest => {
+                command
+                    .args(["nextest", "run", "--no-fail-fast", "--message-format", "libtest-json"])
+                    .env("NEXTEST_EXPERIMENTAL_LIBTEST_JSON", "1");
+            }
+        }
+        let output = command
+            .current_dir(repo_path)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let cases = match harness {
+            TestHarness::Cargo => parse_libtest_output(&stdout),
+            TestHarness::Nextest => parse_libtest_json(&stdout),
+        };
+        
+        // No tests at all on a failed run means the suite never started,
+        // usually because it did not compile.
+        let error = if cases.is_empty() && !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let lines: Vec<&str> = stderr.lines().collect();
+            Some(lines[lines.len().saturating_sub(20)..].join("\n"))
+        } else {
+            None
+        };
+        
+        Ok(TestReport {
+            harness,
+            finished_at: Utc::now(),
+            cases,
+            error,
+            source: None,
+        })
+    }
+    
+    pub fn poll(&mut self) -> Option<TestReport> {
+        self.result.try_recv().ok()
+    }
+    
+    pub fn cancel(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const HUMAN: &str = include_str!("../../tests/fixtures/tests/cargo_test.txt");
+    const JSON: &str = include_str!("../../tests/fixtures/tests/nextest_libtest.jsonl");
+    const JUNIT: &str = include_str!("../../tests/fixtures/tests/junit.xml");
+    
+    #[test]
+    fn human_output_fixture() {
+        let cases = parse_libtest_output(HUMAN);
+        
+        assert_eq!(cases.len(), 6);
+        let report = TestReport {
+            harness: TestHarness::Cargo,
+            finished_at: Utc::now(),
+            cases,
+            error: None,
+            source: None,
+        };
+        assert_eq!(report.count(TestOutcome::Passed), 3);
+        assert_eq!(report.count(TestOutcome::Failed), 2);
+        assert_eq!(report.count(TestOutcome::Ignored), 1);
+        
+        let failures = report.failures();
+        assert_eq!(failures[0].name, "modules::git::tests::staged_rename_is_counted_once");
+        assert!(failures[0].output.starts_with("thread 'modules::git::tests::staged_rename_is_counted_once' panicked"));
+        assert!(failures[0].output.ends_with("right: 1"));
+        assert_eq!(failures[1].name, "parses_empty_config");
+        assert!(failures[1].output.contains("called `Result::unwrap()` on an `Err` value"));
+    }
+    
+    #[test]
+    fn nextest_json_fixture() {
+        let cases = parse_libtest_json(JSON);
+        
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[0].name, "modules::remote::tests::parses_ssh_and_https_remotes");
+        assert_eq!(cases[1].outcome, TestOutcome::Failed);
+        assert!(cases[1].output.contains("assertion failed"));
+        assert_eq!(cases[2].outcome, TestOutcome::Ignored);
+    }
+    
+    #[test]
+    fn junit_fixture() {
+        let cases = parse_junit(JUNIT).unwrap();
+        
+        assert_eq!(cases.len(), 4);
+        assert_eq!(cases[0].name, "devdash::modules::git::tests::counts_staged_files");
+        assert_eq!(cases[0].outcome, TestOutcome::Passed);
+        assert_eq!(cases[1].outcome, TestOutcome::Failed);
+        assert!(cases[1].output.starts_with("thread 'modules::remote::tests::parses_scp_remotes' panicked"));
+        assert!(cases[1].output.contains("left: \"gitlab\""));
+        assert_eq!(cases[2].outcome, TestOutcome::Ignored);
+        assert_eq!(cases[3].name, "parses_empty_config");
+        assert_eq!(cases[3].outcome, TestOutcome::Failed);
+        
+        assert!(parse_junit("<coverage/>").is_err());
+    }
+    
+    #[test]
+    fn only_failures_missing_from_previous_run_are_new() {
+        let report = |cases: Vec<(&str, TestOutcome)>| TestReport {
+            harness: TestHarness::Cargo,
+            finished_at: Utc::now(),
+            cases: cases
+                .into_iter()
+                .map(|(name, outcome)| TestCase { name: name.to_string(), outcome, output: String::new() })
+                .collect(),
+            error: None,
+            source: None,
+        };
+        let previous = report(vec![("a", TestOutcome::Failed), ("b", TestOutcome::Passed)]);
+        let current = report(vec![
+            ("a", TestOutcome::Failed),
+            ("b", TestOutcome::Failed),
+            ("c", TestOutcome::Failed),
+        ]);
+        
+        assert!(!current.is_newly_failing("a", Some(&previous)));
+        assert!(current.is_newly_failing("b", Some(&previous)));
+        assert!(current.is_newly_failing("c", Some(&previous)));
+        assert!(current.is_newly_failing("a", None));
+    }
+    
+    #[test]
+    fn ci_results_between_local_runs_do_not_become_the_baseline() {
+        let report = |failed: &str, source: Option<&str>| TestReport {
+            harness: TestHarness::Cargo,
+            finished_at: Utc::now(),
+            cases: vec![TestCase { name: failed.to_string(), outcome: TestOutcome::Failed, output: String::new() }],
+            error: None,
+            source: source.map(str::to_string),
+        };
+        let mut shown = Some(report("a", None));
+        let mut previous = None;
+        for next in [report("b", Some("ci-tests")), report("b", Some("ci-tests")), report("b", None)] {
+            previous = next_baseline(shown.replace(next), previous);
+        }
+        
+        let baseline = previous.as_ref().unwrap();
+        assert!(baseline.source.is_none());
+        assert_eq!(baseline.cases[0].name, "a");
+        assert!(shown.unwrap().is_newly_failing("b", Some(baseline)));
+    }
+}
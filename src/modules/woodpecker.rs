@@ -0,0 +1,314 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::api::WoodpeckerApi;
+use super::build::{BuildAction, BuildInfo, BuildStatus, JobInfo, StepInfo};
+
+#[derive(Deserialize)]
+struct WoodpeckerRepo {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct WoodpeckerPipeline {
+    number: u64,
+    status: String,
+    #[serde(default)]
+    branch: String,
+    #[serde(default)]
+    commit: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    event: String,
+    #[serde(default)]
+    started: i64,
+    #[serde(default)]
+    finished: i64,
+    #[serde(default)]
+    workflows: Vec<WoodpeckerWorkflow>,
+}
+
+#[derive(Deserialize)]
+struct WoodpeckerWorkflow {
+    id: u64,
+    name: String,
+    state: String,
+    #[serde(default)]
+    started: i64,
+    #[serde(default)]
+    finished: i64,
+    #[serde(default)]
+    children: Vec<WoodpeckerStep>,
+}
+
+#[derive(Deserialize)]
+struct WoodpeckerStep {
+    id: u64,
+    name: String,
+    state: String,
+    #[serde(default)]
+    started: i64,
+    #[serde(default)]
+    finished: i64,
+}
+
+/// Log lines carry their text base64-encoded in `data`.
+#[derive(Deserialize)]
+struct WoodpeckerLogLine {
+    #[serde(default)]
+    data: String,
+}
+
+fn woodpecker_status(state: &str) -> BuildStatus {
+    match state {
+        "success" => BuildStatus::Success,
+        "failure" | "error" => BuildStatus::Failure,
+        "running" => BuildStatus::Running,
+        "pending" | "created" => BuildStatus::Queued,
+        "killed" | "declined" => BuildStatus::Cancelled,
+        "skipped" => BuildStatus::Skipped,
+        "blocked" => BuildStatus::ActionRequired,
+        _ => BuildStatus::Unknown,
+    }
+}
+
+/// Woodpecker uses Unix seconds with 0 for "not yet".
+fn from_unix(seconds: i64) -> Option<DateTime<Utc>> {
+    match seconds {
+        0 => None,
+        s => DateTime::from_timestamp(s, 0),
+    }
+}
+
+fn parse_pipelines(pipelines: Vec<WoodpeckerPipeline>, branch: Option<&str>) -> Vec<BuildInfo> {
+    pipelines
+        .into_iter()
+        .filter(|pipeline| branch.is_none() || branch == Some(pipeline.branch.as_str()))
+        .map(|pipeline| BuildInfo {
+            id: pipeline.number,
+            status: woodpecker_status(&pipeline.status),
+            name: pipeline.event,
+            branch: pipeline.branch,
+            started_at: from_unix(pipeline.started),
+            finished_at: from_unix(pipeline.finished),
+            commit_sha: pipeline.commit.chars().take(7).collect(),
+            message: pipeline.message.lines().next().unwrap_or("").to_string(),
+        })
+        .take(10)
+        .collect()
+}
+
+fn parse_workflows(pipeline: WoodpeckerPipeline) -> Vec<JobInfo> {
+    pipeline
+        .workflows
+        .into_iter()
+        .map(|workflow| JobInfo {
+            id: workflow.id,
+            name: workflow.name,
+            status: woodpecker_status(&workflow.state),
+            started_at: from_unix(workflow.started),
+            completed_at: from_unix(workflow.finished),
+            steps: workflow
+                .children
+                .into_iter()
+                .map(|step| StepInfo {
+                    name: step.name,
+                    status: woodpecker_status(&step.state),
+                    started_at: from_unix(step.started),
+                    completed_at: from_unix(step.finished),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn decode_log(lines: Vec<WoodpeckerLogLine>) -> String {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let mut text = String::new();
+    for line in lines {
+        let data = engine.decode(&line.data).unwrap_or_default();
+        text.push_str(&String::from_utf8_lossy(&data));
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+pub struct WoodpeckerModule;
+
+impl WoodpeckerModule {
+    /// Resolves the forge's `owner/name` to Woodpecker's repository id.
+    pub async fn lookup_repo(api: &WoodpeckerApi, full_name: &str) -> Result<u64> {
+        let response = api
+            .get(&format!("/repos/lookup/{}", full_name))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        let repo: WoodpeckerRepo = response.json().await?;
+        Ok(repo.id)
+    }
+    
+    /// Older servers ignore the `branch` parameter, so results are filtered
+    /// here as well.
+    pub async fn get_status(api: &WoodpeckerApi, repo_id: u64, branch: Option<&str>) -> Result<Vec<BuildInfo>> {
+        let response = api
+            .get(&format!("/repos/{}/pipelines?perPage=10", repo_id))
+            .query(&[("branch", branch)])
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        let pipelines: Vec<WoodpeckerPipeline> = response.json().await?;
+        Ok(parse_pipelines(pipelines, branch))
+    }
+    
+    async fn get_pipeline(api: &WoodpeckerApi, repo_id: u64, number: u64) -> Result<WoodpeckerPipeline> {
+        let response = api
+            .get(&format!("/repos/{}/pipelines/{}", repo_id, number))
+            .send()
+            .await?
+            .error_for_status()?;
+        
+        Ok(response.json().await?)
+    }
+    
+    pub async fn get_jobs(api: &WoodpeckerApi, repo_id: u64, number: u64) -> Result<Vec<JobInfo>> {
+        let pipeline = Self::get_pipeline(api, repo_id, number).await?;
+        Ok(parse_workflows(pipeline))
+    }
+    
+    pub async fn run_action(api: &WoodpeckerApi, repo_id: u64, action: &BuildAction) -> Result<()> {
+        let base = format!("/repos/{}/pipelines", repo_id);
+        let request = match action {
+            BuildAction::Rerun(number) => api.post(&format!("{}/{}", base, number)),
+            BuildAction::RerunFailed(_) => return Err(anyhow!("not supported by Woodpecker")),
+            BuildAction::Cancel(number) => api.post(&format!("{}/{}/cancel", base, number)),
+            BuildAction::Dispatch { git_ref, inputs, .. } => api
+                .post(&base)
+                .json(&serde_json::json!({ "branch": git_ref, "variables": inputs })),
+        };
+        
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+    
+    /// A workflow's log is the logs of its steps, one after another.
+    pub async fn get_job_log(api: &WoodpeckerApi, repo_id: u64, number: u64, workflow_id: u64) -> Result<String> {
+        let pipeline = Self::get_pipeline(api, repo_id, number).await?;
+        let workflow = pipeline
+            .workflows
+            .into_iter()
+            .find(|workflow| workflow.id == workflow_id)
+            .ok_or_else(|| anyhow!("workflow {} not found in pipeline #{}", workflow_id, number))?;
+        
+        let mut log = String::new();
+        for step in workflow.children {
+            let response = api
+                .get(&format!("/repos/{}/logs/{}/{}", repo_id, number, step.id))
+                .send()
+                .await?
+                .error_for_status()?;
+            let lines: Vec<WoodpeckerLogLine> = response.json().await?;
+            log.push_str(&format!("--- {} ---\n", step.name));
+            log.push_str(&decode_log(lines));
+        }
+        Ok(log)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+    
+    const PIPELINES: &str = include_str!("../../tests/fixtures/ci/woodpecker_pipelines.json");
+    const PIPELINE: &str = include_str!("../../tests/fixtures/ci/woodpecker_pipeline.json");
+    const LOGS: &str = include_str!("../../tests/fixtures/ci/woodpecker_logs.json");
+    
+    #[test]
+    fn pipelines_fixture() {
+        let builds = parse_pipelines(serde_json::from_str(PIPELINES).unwrap(), None);
+        
+        assert_eq!(builds.iter().map(|b| b.id).collect::<Vec<_>>(), vec![88, 87, 86]);
+        assert!(matches!(builds[0].status, BuildStatus::Running));
+        assert!(matches!(builds[1].status, BuildStatus::Failure));
+        assert!(matches!(builds[2].status, BuildStatus::Success));
+        assert_eq!(builds[1].commit_sha, "3f2a9c1");
+        assert_eq!(builds[1].message, "Retry uploads on timeout");
+        assert_eq!(builds[1].finished_at.unwrap().to_rfc3339(), "2025-03-07T08:05:30+00:00");
+        assert!(builds[0].finished_at.is_none());
+        
+        let on_main = parse_pipelines(serde_json::from_str(PIPELINES).unwrap(), Some("main"));
+        assert_eq!(on_main.iter().map(|b| b.id).collect::<Vec<_>>(), vec![87, 86]);
+    }
+    
+    #[test]
+    fn workflows_fixture() {
+        let jobs = parse_workflows(serde_json::from_str(PIPELINE).unwrap());
+        
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[1].name, "test");
+        assert!(matches!(jobs[1].status, BuildStatus::Failure));
+        assert_eq!(jobs[1].steps.len(), 2);
+        assert!(matches!(jobs[1].steps[1].status, BuildStatus::Failure));
+    }
+    
+    #[test]
+    fn logs_fixture_is_decoded() {
+        let log = decode_log(serde_json::from_str(LOGS).unwrap());
+        assert_eq!(log, "+ cargo test\nrunning 3 tests\nerror: test failed, to rerun pass `--lib`\n");
+    }
+    
+    #[tokio::test]
+    async fn repo_lookup_logs_and_manual_pipeline() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/repos/lookup/acme/app"))
+            .and(header("Authorization", "Bearer wp-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"id":4,"full_name":"acme/app"}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/repos/4/pipelines/87"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(PIPELINE))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/repos/4/logs/87/14"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("[]"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/repos/4/logs/87/15"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(LOGS))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/repos/4/pipelines"))
+            .and(body_json(serde_json::json!({ "branch": "main", "variables": { "DEPLOY": "1" } })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        
+        let api = WoodpeckerApi::new(reqwest::Client::new(), &server.uri(), "wp-token");
+        let repo_id = WoodpeckerModule::lookup_repo(&api, "acme/app").await.unwrap();
+        assert_eq!(repo_id, 4);
+        
+        let log = WoodpeckerModule::get_job_log(&api, repo_id, 87, 13).await.unwrap();
+        assert!(log.starts_with("--- clone ---\n--- cargo test ---\n+ cargo test\n"));
+        
+        let dispatch = BuildAction::Dispatch {
+            workflow: "manual".to_string(),
+            git_ref: "main".to_string(),
+            inputs: [("DEPLOY".to_string(), "1".to_string())].into(),
+        };
+        WoodpeckerModule::run_action(&api, repo_id, &dispatch).await.unwrap();
+    }
+}
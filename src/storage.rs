@@ -1,8 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
+use crate::modules::coverage::{self, CoverageInfo, CoverageSummary};
+
+const COVERAGE_HISTORY_FILE: &str = "coverage_history.json";
+/// Every snapshot carries all files, so only the most recent are kept.
+const COVERAGE_HISTORY_LIMIT: usize = 100;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimerSession {
     pub start: DateTime<Utc>,
@@ -54,3 +61,157 @@ impl Default for TimerData {
         TimerData { sessions: Vec::new() }
     }
 }
+
+/// Coverage at one point in time. File names are relative to the
+/// repository so snapshots from different checkouts compare.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageSnapshot {
+    pub commit_sha: String,
+    pub taken_at: DateTime<Utc>,
+    pub totals: CoverageSummary,
+    pub files: BTreeMap<String, CoverageSummary>,
+}
+
+impl CoverageSnapshot {
+    pub fn new(coverage: &CoverageInfo, commit_sha: &str, root: &str) -> Self {
+        CoverageSnapshot {
+            commit_sha: commit_sha.to_string(),
+            taken_at: Utc::now(),
+            totals: coverage.totals,
+            files: coverage
+                .files
+                .iter()
+                .map(|file| (coverage::relative_name(&file.name, root).to_string(), file.summary))
+                .collect(),
+        }
+    }
+
+    /// Files whose line coverage dropped compared to `baseline`. Files the
+    /// baseline does not know about are new and never count as regressed.
+    pub fn regressions(&self, baseline: &CoverageSnapshot) -> Vec<&str> {
+        self.files
+            .iter()
+            .filter(|(name, summary)| {
+                baseline
+                    .files
+                    .get(*name)
+                    .is_some_and(|before| summary.lines.is_below(&before.lines))
+            })
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverageHistory {
+    pub snapshots: Vec<CoverageSnapshot>,
+}
+
+impl CoverageHistory {
+    pub fn load() -> Self {
+        match fs::read_to_string(COVERAGE_HISTORY_FILE) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => CoverageHistory::default(),
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(COVERAGE_HISTORY_FILE, content)?;
+        Ok(())
+    }
+
+    /// Appends a snapshot unless it repeats the last one, as happens when a
+    /// report is reloaded without a new run, and drops the oldest beyond
+    /// `COVERAGE_HISTORY_LIMIT`. Returns whether it was added.
+    pub fn record(&mut self, snapshot: CoverageSnapshot) -> bool {
+        let repeated = self.snapshots.last().is_some_and(|last| {
+            last.commit_sha == snapshot.commit_sha && last.totals == snapshot.totals && last.files == snapshot.files
+        });
+        if !repeated {
+            self.snapshots.push(snapshot);
+            let excess = self.snapshots.len().saturating_sub(COVERAGE_HISTORY_LIMIT);
+            self.snapshots.drain(..excess);
+        }
+        !repeated
+    }
+
+    pub fn latest(&self) -> Option<&CoverageSnapshot> {
+        self.snapshots.last()
+    }
+
+    /// The snapshot before the latest one.
+    pub fn previous(&self) -> Option<&CoverageSnapshot> {
+        self.snapshots.iter().rev().nth(1)
+    }
+
+    /// The most recent snapshot taken at `commit_sha`.
+    pub fn at_commit(&self, commit_sha: &str) -> Option<&CoverageSnapshot> {
+        self.snapshots.iter().rev().find(|snapshot| snapshot.commit_sha == commit_sha)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::coverage::Counts;
+
+    fn snapshot(sha: &str, files: &[(&str, u64, u64)]) -> CoverageSnapshot {
+        let files: BTreeMap<String, CoverageSummary> = files
+            .iter()
+            .map(|&(name, count, covered)| {
+                let summary = CoverageSummary {
+                    lines: Counts { count, covered },
+                    ..Default::default()
+                };
+                (name.to_string(), summary)
+            })
+            .collect();
+        CoverageSnapshot {
+            commit_sha: sha.to_string(),
+            taken_at: Utc::now(),
+            totals: CoverageSummary::default(),
+            files,
+        }
+    }
+
+    #[test]
+    fn repeated_snapshots_are_recorded_once() {
+        let mut history = CoverageHistory::default();
+        assert!(history.record(snapshot("a1", &[("src/app.rs", 10, 5)])));
+        assert!(!history.record(snapshot("a1", &[("src/app.rs", 10, 5)])));
+        assert!(history.record(snapshot("a1", &[("src/app.rs", 10, 6)])));
+        assert!(history.record(snapshot("b2", &[("src/app.rs", 10, 6)])));
+
+        assert_eq!(history.snapshots.len(), 3);
+        assert_eq!(history.latest().unwrap().commit_sha, "b2");
+        assert_eq!(history.previous().unwrap().commit_sha, "a1");
+        assert_eq!(history.at_commit("a1").unwrap().files["src/app.rs"].lines.covered, 6);
+        assert!(history.at_commit("c3").is_none());
+    }
+
+    #[test]
+    fn history_keeps_only_the_latest_snapshots() {
+        let mut history = CoverageHistory::default();
+        for i in 0..COVERAGE_HISTORY_LIMIT + 5 {
+            history.record(snapshot(&i.to_string(), &[("src/app.rs", 10, 5)]));
+        }
+
+        assert_eq!(history.snapshots.len(), COVERAGE_HISTORY_LIMIT);
+        assert_eq!(history.snapshots[0].commit_sha, "5");
+        assert_eq!(history.latest().unwrap().commit_sha, (COVERAGE_HISTORY_LIMIT + 4).to_string());
+    }
+
+    #[test]
+    fn lower_line_coverage_is_a_regression() {
+        let before = snapshot("a1", &[("src/app.rs", 10, 5), ("src/git.rs", 3, 1), ("src/ui.rs", 4, 4)]);
+        // 2/6 equals 1/3 exactly; new files have nothing to compare with.
+        let after = snapshot(
+            "b2",
+            &[("src/app.rs", 12, 5), ("src/git.rs", 6, 2), ("src/ui.rs", 4, 4), ("src/new.rs", 5, 0)],
+        );
+
+        assert_eq!(after.regressions(&before), ["src/app.rs"]);
+        assert!(before.regressions(&before).is_empty());
+    }
+}
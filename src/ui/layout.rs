@@ -1,9 +1,20 @@
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Sparkline, Table, TableState, Tabs},
     Frame,
 };
 
+use ansi_to_tui::IntoText;
+
+use crate::modules::build::{self, BuildStatus};
+use crate::config::CoverageThresholds;
+use crate::modules::cargo::DiagnosticLevel;
+use crate::modules::coverage::{self, CoverageRow, Counts, SourceView};
+use crate::modules::logs::{self, LogView};
+use crate::modules::test_results::TestOutcome;
+use crate::storage::CoverageSnapshot;
 use crate::ui::themes::Theme;
 use super::widgets;
 
@@ -20,9 +31,24 @@ pub fn render_dashboard(frame: &mut Frame, app: &crate::app::App, theme: &Theme)
     render_header(frame, chunks[0], app, theme);
     render_body(frame, chunks[1], app, theme);
     render_footer(frame, chunks[2], theme);
+    
+    if let Some(action) = &app.pending_action {
+        let hint = match action {
+            build::BuildAction::Dispatch { .. } => "[y] confirm  [n] cancel  [Left/Right] preset",
+            _ => "[y] confirm  [n] cancel",
+        };
+        let area = widgets::centered_rect(60, 6, frame.area());
+        frame.render_widget(Clear, area);
+        frame.render_widget(widgets::confirm_dialog(action.describe(), hint, theme), area);
+    }
 }
 
 fn render_header(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Length(1)])
+        .split(area);
+    
     let title = format!("DevDash - Painel do Projeto: {}", &app.project_name());
     
     let header = Paragraph::new(title)
@@ -30,10 +56,28 @@ fn render_header(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &T
         .style(theme.primary())
         .block(Block::default().style(theme.surface()).borders(Borders::NONE));
     
-    frame.render_widget(header, area);
+    let tabs = Tabs::new(crate::app::TABS.to_vec())
+        .select(app.current_tab)
+        .style(theme.text_secondary())
+        .highlight_style(theme.primary())
+        .block(Block::default().style(theme.surface()));
+    
+    frame.render_widget(header, chunks[0]);
+    frame.render_widget(tabs, chunks[1]);
 }
 
 fn render_body(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    match app.current_tab {
+        1 => render_git_tab(frame, area, app, theme),
+        2 => render_build_tab(frame, area, app, theme),
+        3 => render_pulls_tab(frame, area, app, theme),
+        4 => render_tests_tab(frame, area, app, theme),
+        5 => render_coverage_tab(frame, area, app, theme),
+        _ => render_overview(frame, area, app, theme),
+    }
+}
+
+fn render_overview(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -48,6 +92,138 @@ fn render_body(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &The
     render_logs(frame, chunks[2], app, theme);
 }
 
+fn render_git_tab(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(13), Constraint::Length(9)])
+        .split(area);
+    
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rows[0]);
+    
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(35),
+            Constraint::Percentage(35),
+        ])
+        .split(rows[1]);
+    
+    render_git_activity(frame, top[0], app, theme);
+    render_git_rankings(frame, top[1], app, theme);
+    render_git_changes(frame, bottom[0], app, theme);
+    render_submodules(frame, bottom[1], app, theme);
+    render_worktrees(frame, bottom[2], app, theme);
+}
+
+fn render_git_changes(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let changes = &app.git_status.changes;
+    let (staged, unstaged) = (&changes.staged, &changes.unstaged);
+    
+    let mut content = format!(
+        "            staged  unstaged\nAdded:      {:>6}\nModified:   {:>6}  {:>8}\nDeleted:    {:>6}  {:>8}\nRenamed:    {:>6}  {:>8}\nTypechange: {:>6}  {:>8}\nConflicted: {}  Untracked: {}",
+        staged.added,
+        staged.modified, unstaged.modified,
+        staged.deleted, unstaged.deleted,
+        staged.renamed, unstaged.renamed,
+        staged.typechange, unstaged.typechange,
+        changes.conflicted, changes.untracked
+    );
+    if app.config.show_ignored {
+        content.push_str(&format!("  Ignored: {}", changes.ignored));
+    }
+    
+    let style = if changes.conflicted > 0 { theme.error() } else { theme.text() };
+    let widget = Paragraph::new(content)
+        .block(widgets::panel_block("Changes", theme))
+        .style(style);
+    frame.render_widget(widget, area);
+}
+
+fn render_submodules(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    use crate::modules::git::SubmoduleState;
+    
+    let items: Vec<ListItem> = if app.git_status.submodules.is_empty() {
+        vec![ListItem::new("No submodules").style(theme.text_secondary())]
+    } else {
+        app.git_status
+            .submodules
+            .iter()
+            .map(|sm| {
+                let (label, style) = match sm.state {
+                    SubmoduleState::Clean => ("[OK]", theme.success()),
+                    SubmoduleState::OutOfDate => ("[OUTDATED]", theme.warning()),
+                    SubmoduleState::Dirty => ("[DIRTY]", theme.warning()),
+                    SubmoduleState::Uninitialized => ("[UNINIT]", theme.error()),
+                };
+                ListItem::new(format!("{:<11}{}", label, sm.path)).style(style)
+            })
+            .collect()
+    };
+    
+    let list = List::new(items).block(widgets::panel_block("Submodules", theme));
+    frame.render_widget(list, area);
+}
+
+fn render_worktrees(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let items: Vec<ListItem> = if app.git_status.worktrees.is_empty() {
+        vec![ListItem::new("No linked worktrees").style(theme.text_secondary())]
+    } else {
+        app.git_status
+            .worktrees
+            .iter()
+            .map(|wt| ListItem::new(format!("{} ({}) {}", wt.name, wt.branch, wt.path)).style(theme.text()))
+            .collect()
+    };
+    
+    let list = List::new(items).block(widgets::panel_block("Worktrees", theme));
+    frame.render_widget(list, area);
+}
+
+fn render_git_activity(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let insights = &app.insights;
+    let per_day = insights.commits_per_day();
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(9), Constraint::Min(4)])
+        .split(area);
+    
+    let title = format!("Commits - last {} weeks", insights.weeks);
+    let heatmap = widgets::heatmap(&title, &per_day, insights.first_day(), theme);
+    frame.render_widget(heatmap, chunks[0]);
+    
+    let sparkline = Sparkline::default()
+        .block(widgets::panel_block("Daily Activity", theme))
+        .data(&per_day)
+        .style(theme.success());
+    frame.render_widget(sparkline, chunks[1]);
+}
+
+fn render_git_rankings(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let insights = &app.insights;
+    let mut content = format!(
+        "Commits: {}\nAvg size: {:.1} lines\n\nTop authors:\n",
+        insights.commits.len(),
+        insights.average_commit_size()
+    );
+    for (author, count) in insights.top_authors(5) {
+        content.push_str(&format!("  {:>4}  {}\n", count, author));
+    }
+    content.push_str("\nMost churned files:\n");
+    for (path, lines) in insights.most_churned_files(8) {
+        content.push_str(&format!("  {:>6}  {}\n", lines, path));
+    }
+    
+    let widget = Paragraph::new(content)
+        .block(widgets::panel_block("Insights", theme))
+        .style(theme.text());
+    frame.render_widget(widget, area);
+}
+
 fn render_system_stats(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
     let stats = &app.system_stats;
     let ram_percent = if stats.ram_total > 0 {
@@ -112,7 +288,7 @@ fn render_git_status(frame: &mut Frame, area: Rect, app: &crate::app::App, theme
     let content = format!(
         "Branch: {}\nCommits ^: {}\nCommits v: {}\nStaged: {}\nUnstaged: {}\nUntracked: {}\nLast: {}",
         git_info.branch, git_info.commits_ahead, git_info.commits_behind,
-        git_info.staged, git_info.unstaged, git_info.untracked,
+        git_info.changes.staged.total(), git_info.changes.unstaged.total(), git_info.changes.untracked,
         commit_msg
     );
     
@@ -120,34 +296,692 @@ fn render_git_status(frame: &mut Frame, area: Rect, app: &crate::app::App, theme
     frame.render_widget(widget, area);
 }
 
-fn render_build_status(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
-    let focused = app.focused_panel == 1;
-    let builds = &app.builds;
-    let content = if builds.is_empty() {
-        "No builds".to_string()
+fn status_indicator(status: &BuildStatus) -> &'static str {
+    match status {
+        BuildStatus::Success => "[OK]",
+        BuildStatus::Failure => "[FAIL]",
+        BuildStatus::Running => "[RUN]",
+        BuildStatus::Queued => "[WAIT]",
+        BuildStatus::Cancelled => "[STOP]",
+        BuildStatus::Skipped => "[SKIP]",
+        BuildStatus::TimedOut => "[TIME]",
+        BuildStatus::ActionRequired => "[ACT]",
+        BuildStatus::Manual => "[MAN]",
+        BuildStatus::Unknown => "[?]",
+    }
+}
+
+fn status_style(status: &BuildStatus, theme: &Theme) -> Style {
+    match status {
+        BuildStatus::Success => theme.success(),
+        BuildStatus::Failure | BuildStatus::TimedOut => theme.error(),
+        BuildStatus::Running => theme.warning(),
+        BuildStatus::Queued => theme.secondary(),
+        BuildStatus::ActionRequired | BuildStatus::Manual => theme.accent(),
+        BuildStatus::Cancelled | BuildStatus::Skipped | BuildStatus::Unknown => theme.text_secondary(),
+    }
+}
+
+fn format_duration(duration: Option<chrono::Duration>) -> String {
+    match duration {
+        Some(d) if d.num_hours() > 0 => format!("{}h {:02}m", d.num_hours(), d.num_minutes() % 60),
+        Some(d) => format!("{}m {:02}s", d.num_minutes(), d.num_seconds() % 60),
+        None => "-".to_string(),
+    }
+}
+
+fn render_build_tab(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    if let Some(view) = &app.log_view {
+        render_log_view(frame, area, view, theme);
+        return;
+    }
+    
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(area);
+    
+    let left = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(chunks[0]);
+    
+    render_build_runs(frame, left[0], app, theme);
+    render_local_build(frame, left[1], app, theme);
+    
+    if app.artifacts.is_empty() {
+        render_build_jobs(frame, chunks[1], app, theme);
+        return;
+    }
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(chunks[1]);
+    render_build_jobs(frame, right[0], app, theme);
+    render_artifacts(frame, right[1], app, theme);
+}
+
+fn render_artifacts(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let items: Vec<ListItem> = app
+        .artifacts
+        .iter()
+        .map(|artifact| {
+            let size = format!("{:.1} MB", artifact.size as f64 / 1024.0 / 1024.0);
+            if artifact.expired {
+                ListItem::new(format!("{:<10}{} (expired)", size, artifact.name)).style(theme.text_secondary())
+            } else {
+                ListItem::new(format!("{:<10}{}", size, artifact.name)).style(theme.text())
+            }
+        })
+        .collect();
+    
+    let title = format!("Artifacts [x] next [s] save to {}", app.config.artifact_dir);
+    let list = List::new(items)
+        .block(widgets::panel_block(&title, theme))
+        .highlight_style(Style::default().bg(theme.background).add_modifier(Modifier::BOLD));
+    let mut state = ListState::default().with_selected(Some(app.selected_artifact));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_local_build(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let mut items = Vec::new();
+    match &app.local_build {
+        Some(local) => {
+            let elapsed = local.finished_at.unwrap_or_else(chrono::Utc::now) - local.started_at;
+            let progress = if local.is_running() {
+                format!("{} crates", local.artifacts)
+            } else {
+                format_duration(Some(elapsed))
+            };
+            items.push(
+                ListItem::new(format!("{:<7}{}  {}", status_indicator(&local.status()), local.summary(), progress))
+                    .style(status_style(&local.status(), theme).add_modifier(Modifier::BOLD)),
+            );
+            for diagnostic in &local.diagnostics {
+                let (tag, style) = match diagnostic.level {
+                    DiagnosticLevel::Error => ("E", theme.error()),
+                    DiagnosticLevel::Warning => ("W", theme.warning()),
+                };
+                items.push(ListItem::new(format!(" {} {} {}", tag, diagnostic.location(), diagnostic.message)).style(style));
+            }
+        }
+        None => items.push(
+            ListItem::new(format!("Press l to run {}", app.local_command.label())).style(theme.text_secondary()),
+        ),
+    }
+    
+    let title = format!("Local: {} [l] run/stop [k] command", app.local_command.label());
+    frame.render_widget(List::new(items).block(widgets::panel_block(&title, theme)), area);
+}
+
+fn render_build_runs(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let now = chrono::Utc::now();
+    let items: Vec<ListItem> = app
+        .builds
+        .iter()
+        .map(|run| {
+            let marker = if Some(run.id) == app.jobs_run_id { ">" } else { " " };
+            let head = if app.is_head_run(run) { "*" } else { " " };
+            ListItem::new(format!(
+                "{}{:<7}{}{} {:<8} {:>8} {}",
+                marker,
+                status_indicator(&run.status),
+                head,
+                run.commit_sha,
+                format_duration(build::duration(run.started_at, run.finished_at)),
+                run.started_at.map(|t| build::relative_time(t, now)).unwrap_or_default(),
+                run.name
+            ))
+            .style(status_style(&run.status, theme))
+        })
+        .collect();
+    
+    let provider = app.ci.as_ref().map_or("CI", |ci| ci.name());
+    let mut title = format!("{} runs: {} [b] (* = HEAD)", provider, app.run_filter.label());
+    if let Some(quota) = rate_limit_text(app) {
+        title.push_str(&format!(" {}", quota));
+    }
+    let list = List::new(items)
+        .block(widgets::panel_block(&title, theme))
+        .highlight_style(Style::default().bg(theme.background).add_modifier(Modifier::BOLD));
+    
+    let mut state = ListState::default().with_selected(Some(app.selected_build));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_build_jobs(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let mut items = Vec::new();
+    let mut selected_row = None;
+    for (index, job) in app.jobs.iter().enumerate() {
+        if index == app.selected_job {
+            selected_row = Some(items.len());
+        }
+        items.push(
+            ListItem::new(format!(
+                "{:<7}{}  {}",
+                status_indicator(&job.status),
+                job.name,
+                format_duration(build::duration(job.started_at, job.completed_at))
+            ))
+            .style(status_style(&job.status, theme).add_modifier(Modifier::BOLD)),
+        );
+        for step in &job.steps {
+            items.push(
+                ListItem::new(format!(
+                    "   {:<7}{}  {}",
+                    status_indicator(&step.status),
+                    step.name,
+                    format_duration(build::duration(step.started_at, step.completed_at))
+                ))
+                .style(status_style(&step.status, theme)),
+            );
+        }
+    }
+    if items.is_empty() {
+        items.push(ListItem::new("Select a run and press Enter to load its jobs").style(theme.text_secondary()));
+    }
+    
+    let title = if app.jobs_focused { "Jobs & Steps [Enter: log]" } else { "Jobs & Steps [Right: focus]" };
+    let list = List::new(items)
+        .block(widgets::panel_block(title, theme))
+        .highlight_style(Style::default().bg(theme.background).add_modifier(Modifier::BOLD));
+    
+    let mut state = ListState::default().with_selected(selected_row.filter(|_| app.jobs_focused));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_log_view(frame: &mut Frame, area: Rect, view: &LogView, theme: &Theme) {
+    let height = area.height.saturating_sub(2) as usize;
+    let width = view.lines.len().to_string().len();
+    
+    let lines: Vec<Line> = view
+        .lines
+        .iter()
+        .enumerate()
+        .skip(view.scroll)
+        .take(height)
+        .map(|(index, raw)| {
+            let is_error = view.error_lines.binary_search(&index).is_ok();
+            let is_match = view.matches.binary_search(&index).is_ok();
+            
+            let gutter_style = if is_error { theme.error() } else { theme.text_secondary() };
+            let mut spans = vec![Span::styled(format!("{:>width$} ", index + 1), gutter_style)];
+            match raw.into_text() {
+                Ok(text) => spans.extend(text.lines.into_iter().flat_map(|line| line.spans)),
+                Err(_) => spans.push(Span::raw(logs::strip_ansi(raw))),
+            }
+            
+            let line = Line::from(spans);
+            if is_match {
+                line.patch_style(Style::default().bg(theme.background).add_modifier(Modifier::BOLD))
+            } else {
+                line
+            }
+        })
+        .collect();
+    
+    let title = format!(
+        "{} - line {}/{} - {} errors [/ search, n/N, e first error, Esc close]",
+        view.title,
+        view.scroll + 1,
+        view.lines.len(),
+        view.error_lines.len()
+    );
+    let mut block = widgets::panel_block(&title, theme);
+    if view.searching || !view.query.is_empty() {
+        let prompt = format!(
+            " /{}{} ({} matches) ",
+            view.query,
+            if view.searching { "_" } else { "" },
+            view.matches.len()
+        );
+        block = block.title_bottom(Line::styled(prompt, theme.primary()));
+    }
+    
+    let widget = Paragraph::new(lines).block(block).style(theme.text());
+    frame.render_widget(widget, area);
+}
+
+fn render_tests_tab(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(area);
+    let left = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(5), Constraint::Min(0)])
+        .split(chunks[0]);
+    
+    let mut summary = Vec::new();
+    if let Some(run) = &app.test_run {
+        let elapsed = chrono::Utc::now() - run.started_at;
+        summary.push(Line::styled(
+            format!("[RUN]  {} for {}", run.harness.label(), format_duration(Some(elapsed))),
+            theme.warning(),
+        ));
+    }
+    match &app.test_report {
+        Some(report) => {
+            summary.push(Line::from(vec![
+                Span::styled(format!("{} passed  ", report.count(TestOutcome::Passed)), theme.success()),
+                Span::styled(format!("{} failed  ", report.count(TestOutcome::Failed)), theme.error()),
+                Span::styled(format!("{} ignored", report.count(TestOutcome::Ignored)), theme.text_secondary()),
+            ]));
+            let source = report.source.as_deref().unwrap_or(report.harness.label());
+            summary.push(Line::styled(
+                format!("{} at {}", source, report.finished_at.format("%Y-%m-%d %H:%M")),
+                theme.text_secondary(),
+            ));
+        }
+        None if app.test_run.is_none() => {
+            summary.push(Line::styled("No test results yet", theme.text_secondary()));
+        }
+        None => {}
+    }
+    let summary = Paragraph::new(summary).block(widgets::panel_block("Tests [t] run/stop", theme));
+    frame.render_widget(summary, left[0]);
+    
+    let previous = app.previous_test_report.as_ref();
+    let failures = app.test_report.as_ref().map(|report| report.failures()).unwrap_or_default();
+    let items: Vec<ListItem> = failures
+        .iter()
+        .map(|case| {
+            let newly_failing = app
+                .test_report
+                .as_ref()
+                .is_some_and(|report| report.is_newly_failing(&case.name, previous));
+            if newly_failing {
+                ListItem::new(format!("NEW {}", case.name)).style(theme.error().add_modifier(Modifier::BOLD))
+            } else {
+                ListItem::new(format!("    {}", case.name)).style(theme.error())
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .block(widgets::panel_block("Failing", theme))
+        .highlight_style(Style::default().bg(theme.background).add_modifier(Modifier::BOLD));
+    let mut state = ListState::default().with_selected(Some(app.selected_test).filter(|_| !failures.is_empty()));
+    frame.render_stateful_widget(list, left[1], &mut state);
+    
+    let (title, output) = match (app.selected_failure(), app.test_report.as_ref()) {
+        (Some(case), _) => (case.name.as_str(), case.output.as_str()),
+        (None, Some(report)) => ("Output", report.error.as_deref().unwrap_or("")),
+        (None, None) => ("Output", ""),
+    };
+    let output = Paragraph::new(output)
+        .style(theme.text())
+        .block(widgets::panel_block(title, theme))
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    frame.render_widget(output, chunks[1]);
+}
+
+fn coverage_style(percent: f64, thresholds: &CoverageThresholds, theme: &Theme) -> Style {
+    if percent < thresholds.low {
+        theme.error()
+    } else if percent < thresholds.high {
+        theme.warning()
     } else {
-        let status_indicator = match builds[0].status {
-            crate::modules::build::BuildStatus::Success => "[OK]",
-            crate::modules::build::BuildStatus::Failure => "[FAIL]",
-            crate::modules::build::BuildStatus::Running => "[RUN]",
-            crate::modules::build::BuildStatus::Unknown => "[?]",
-        };
-        let time_str = if builds[0].timestamp.is_empty() {
-            "now".to_string()
-        } else if builds[0].timestamp.len() > 19 {
-            builds[0].timestamp[..19].to_string()
+        theme.success()
+    }
+}
+
+fn coverage_span(label: &str, counts: Counts, thresholds: &CoverageThresholds, theme: &Theme) -> Span<'static> {
+    if counts.count == 0 {
+        return Span::styled(format!("{} -  ", label), theme.text_secondary());
+    }
+    Span::styled(
+        format!("{} {:.1}% ({}/{})  ", label, counts.percent(), counts.covered, counts.count),
+        coverage_style(counts.percent(), thresholds, theme),
+    )
+}
+
+fn render_coverage_tab(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    if let Some(view) = &app.source_view {
+        render_source_view(frame, area, view, theme);
+        return;
+    }
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(6), Constraint::Min(0)])
+        .split(area);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(chunks[0]);
+    let thresholds = &app.config.coverage_thresholds;
+    let baseline = app.coverage_baseline_snapshot();
+    
+    let totals = app.coverage.totals;
+    let mut summary = vec![Line::from(vec![
+        coverage_span("Lines", totals.lines, thresholds, theme),
+        coverage_span("Functions", totals.functions, thresholds, theme),
+        coverage_span("Regions", totals.regions, thresholds, theme),
+        coverage_span("Branches", totals.branches, thresholds, theme),
+    ])];
+    if let Some(run) = &app.coverage_run {
+        let elapsed = chrono::Utc::now() - run.started_at;
+        summary.push(Line::styled(
+            format!("[RUN]  cargo llvm-cov for {}: {}", format_duration(Some(elapsed)), run.progress),
+            theme.warning(),
+        ));
+    } else if let Some(source) = &app.coverage.source {
+        summary.push(Line::styled(format!("From CI: {}", source), theme.text_secondary()));
+    }
+    summary.push(match baseline {
+        Some(snapshot) => {
+            let change = totals.lines.percent() - snapshot.totals.lines.percent();
+            let sha: String = snapshot.commit_sha.chars().take(7).collect();
+            let mut spans = vec![Span::styled(
+                format!(
+                    "vs {} ({} {}): {:+.1}% lines",
+                    app.coverage_baseline.label(),
+                    sha,
+                    build::relative_time(snapshot.taken_at, chrono::Utc::now()),
+                    change
+                ),
+                if totals.lines.is_below(&snapshot.totals.lines) { theme.error() } else { theme.text_secondary() },
+            )];
+            let current = CoverageSnapshot::new(&app.coverage, "", &app.repo_root);
+            let regressed = current.regressions(snapshot).len();
+            if regressed > 0 {
+                spans.push(Span::styled(
+                    format!("  {} file(s) regressed", regressed),
+                    theme.error().add_modifier(Modifier::BOLD),
+                ));
+            }
+            Line::from(spans)
+        }
+        None => Line::styled(format!("No {} to compare with", app.coverage_baseline.label()), theme.text_secondary()),
+    });
+    summary.push(match &app.patch_coverage {
+        Some(patch) if patch.lines.count > 0 => Line::from(vec![
+            coverage_span(&format!("Patch ({})", app.patch_scope.label()), patch.lines, thresholds, theme),
+            Span::styled(format!("{} changed lines uncovered", patch.uncovered.len()), theme.text_secondary()),
+        ]),
+        _ => Line::styled(
+            format!("Patch ({}): no changed code", app.patch_scope.label()),
+            theme.text_secondary(),
+        ),
+    });
+    let title = if app.coverage_tree {
+        "Coverage by directory - [g] files  [Enter] open  [b] baseline  [p] patch  [v] run/stop llvm-cov".to_string()
+    } else {
+        format!(
+            "Coverage by {} - [o] sort  [g] directories  [Enter] open  [b] baseline  [p] patch  [v] run/stop llvm-cov",
+            app.coverage_sort.label()
+        )
+    };
+    let summary = Paragraph::new(summary).block(widgets::panel_block(&title, theme));
+    frame.render_widget(summary, top[0]);
+    
+    // Tenths of a percent, on a fixed 0-100% scale so small changes do not
+    // look dramatic.
+    let trend: Vec<u64> = app
+        .coverage_history
+        .snapshots
+        .iter()
+        .map(|snapshot| (snapshot.totals.lines.percent() * 10.0) as u64)
+        .collect();
+    let visible = trend.len().saturating_sub(top[1].width.saturating_sub(2) as usize);
+    let trend_title = format!("Trend ({} snapshots)", trend.len());
+    let sparkline = Sparkline::default()
+        .block(widgets::panel_block(&trend_title, theme))
+        .data(&trend[visible..])
+        .max(1000)
+        .style(theme.success());
+    frame.render_widget(sparkline, top[1]);
+    
+    let cell = |counts: Counts| {
+        if counts.count == 0 {
+            Cell::from("-").style(theme.text_secondary())
         } else {
-            builds[0].timestamp.clone()
+            Cell::from(format!("{:5.1}% {}/{}", counts.percent(), counts.covered, counts.count))
+                .style(coverage_style(counts.percent(), thresholds, theme))
+        }
+    };
+    let change = |row: &CoverageRow| {
+        let (Some(baseline), Some(index)) = (baseline, row.file) else {
+            return Cell::from("");
+        };
+        let name = coverage::relative_name(&app.coverage.files[index].name, &app.repo_root);
+        let Some(before) = baseline.files.get(name) else {
+            return Cell::from("new").style(theme.text_secondary());
         };
-        format!("Status: {}\nName: {}\nTime: {}", status_indicator, builds[0].name, time_str)
+        let delta = row.summary.lines.percent() - before.lines.percent();
+        if row.summary.lines.is_below(&before.lines) {
+            Cell::from(format!("{:+.1} REGR", delta)).style(theme.error().add_modifier(Modifier::BOLD))
+        } else if before.lines.is_below(&row.summary.lines) {
+            Cell::from(format!("{:+.1}", delta)).style(theme.success())
+        } else {
+            Cell::from("=").style(theme.text_secondary())
+        }
     };
+    let header = Row::new(["Path", "Lines", "Functions", "Branches", "Uncovered", "Change"])
+        .style(theme.primary().add_modifier(Modifier::BOLD));
+    let rows: Vec<CoverageRow> = app.coverage_rows();
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .map(|row| {
+            let name = match row.file {
+                Some(_) => format!("{}{}", "  ".repeat(row.depth), row.name),
+                None => format!("{}{}/", "  ".repeat(row.depth), row.name),
+            };
+            let name_style = if row.file.is_some() { theme.text() } else { theme.primary() };
+            Row::new(vec![
+                Cell::from(name).style(name_style),
+                cell(row.summary.lines),
+                cell(row.summary.functions),
+                cell(row.summary.branches),
+                Cell::from(row.summary.lines.uncovered().to_string()).style(theme.text_secondary()),
+                change(row),
+            ])
+        })
+        .collect();
+    
+    let widths = [
+        Constraint::Min(30),
+        Constraint::Length(18),
+        Constraint::Length(18),
+        Constraint::Length(18),
+        Constraint::Length(10),
+        Constraint::Length(11),
+    ];
+    let files_title = format!("Files ({})", app.coverage.files.len());
+    let table = Table::new(table_rows, widths)
+        .header(header)
+        .block(widgets::panel_block(&files_title, theme))
+        .highlight_style(Style::default().bg(theme.background).add_modifier(Modifier::BOLD));
+    let selected = app.selected_coverage.min(rows.len().saturating_sub(1));
+    let mut state = TableState::default().with_selected(Some(selected).filter(|_| !rows.is_empty()));
+    
+    let uncovered = app.patch_coverage.as_ref().map(|patch| patch.uncovered.as_slice()).unwrap_or_default();
+    if uncovered.is_empty() {
+        frame.render_stateful_widget(table, chunks[1], &mut state);
+        return;
+    }
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+        .split(chunks[1]);
+    frame.render_stateful_widget(table, bottom[0], &mut state);
+    
+    let items: Vec<ListItem> = uncovered
+        .iter()
+        .map(|(path, line)| ListItem::new(format!("{}:{}", path, line)).style(theme.error()))
+        .collect();
+    let list = List::new(items).block(widgets::panel_block("Uncovered changes", theme));
+    frame.render_widget(list, bottom[1]);
+}
+
+fn render_source_view(frame: &mut Frame, area: Rect, view: &SourceView, theme: &Theme) {
+    let height = area.height.saturating_sub(2) as usize;
+    let width = view.lines.len().to_string().len();
+    
+    let lines: Vec<Line> = view
+        .lines
+        .iter()
+        .enumerate()
+        .skip(view.scroll)
+        .take(height)
+        .map(|(index, code)| {
+            let number = Span::styled(format!("{:>width$} ", index + 1), theme.text_secondary());
+            let (count, style) = match view.hits(index) {
+                Some(0) => ("0".to_string(), theme.error()),
+                Some(hits) => (hits.to_string(), theme.success()),
+                None => (String::new(), theme.text_secondary()),
+            };
+            let gutter = Span::styled(format!("{:>7} | ", count), style);
+            let code_style = if view.hits(index) == Some(0) { theme.error() } else { theme.text() };
+            Line::from(vec![number, gutter, Span::styled(code.replace('\t', "    "), code_style)])
+        })
+        .collect();
+    
+    let title = format!(
+        "{} - line {}/{} - {} uncovered lines [n/N next/previous uncovered, Esc close]",
+        view.title,
+        view.scroll + 1,
+        view.lines.len(),
+        view.uncovered_lines()
+    );
+    let widget = Paragraph::new(lines).block(widgets::panel_block(&title, theme)).style(theme.text());
+    frame.render_widget(widget, area);
+}
+
+fn render_pulls_tab(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let header = Row::new(["#", "Title", "Author", "Review", "Checks", "Merge", "Labels"])
+        .style(theme.primary().add_modifier(Modifier::BOLD));
+    
+    let rows: Vec<Row> = app
+        .pulls
+        .iter()
+        .map(|pr| {
+            let pinned = pr.branch == app.git_status.branch;
+            let title = match (pinned, pr.draft) {
+                (true, true) => format!("* [draft] {}", pr.title),
+                (true, false) => format!("* {}", pr.title),
+                (false, true) => format!("[draft] {}", pr.title),
+                (false, false) => pr.title.clone(),
+            };
+            let merge = match pr.mergeable {
+                Some(true) => "yes",
+                Some(false) => "conflict",
+                None => "?",
+            };
+            let review_style = match pr.review_state.as_str() {
+                "approved" => theme.success(),
+                "changes requested" => theme.error(),
+                _ => theme.text(),
+            };
+            
+            let row = Row::new(vec![
+                Cell::from(format!("{}", pr.number)),
+                Cell::from(title),
+                Cell::from(pr.author.clone()),
+                Cell::from(pr.review_state.clone()).style(review_style),
+                Cell::from(status_indicator(&pr.checks)).style(status_style(&pr.checks, theme)),
+                Cell::from(merge).style(if pr.mergeable == Some(false) { theme.error() } else { theme.text() }),
+                Cell::from(pr.labels.join(", ")).style(theme.text_secondary()),
+            ]);
+            
+            if pr.review_requested {
+                row.style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+            } else {
+                row.style(theme.text())
+            }
+        })
+        .collect();
+    
+    let widths = [
+        Constraint::Length(6),
+        Constraint::Min(30),
+        Constraint::Length(16),
+        Constraint::Length(18),
+        Constraint::Length(7),
+        Constraint::Length(9),
+        Constraint::Length(24),
+    ];
+    let title = format!(
+        "Open Pull Requests ({}) - * current branch, highlighted = review requested",
+        app.pulls.len()
+    );
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(widgets::panel_block(&title, theme));
+    frame.render_widget(table, area);
+}
+
+/// Remaining API quota, or when polling resumes if it ran out.
+fn rate_limit_text(app: &crate::app::App) -> Option<String> {
+    let limit = app.ci.as_ref()?.rate_limit()?;
+    let now = chrono::Utc::now();
+    match (limit.blocked_until, limit.remaining, limit.limit) {
+        (Some(until), _, _) if limit.is_blocked(now) => {
+            Some(format!("API: rate limited until {}", until.with_timezone(&chrono::Local).format("%H:%M")))
+        }
+        (_, Some(remaining), Some(total)) => Some(format!("API: {}/{}", remaining, total)),
+        _ => None,
+    }
+}
+
+fn render_build_status(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
+    let focused = app.focused_panel == 1;
+    let builds = &app.builds;
+    if builds.is_empty() {
+        frame.render_widget(widgets::status_box("Build Status", "No builds", theme, focused), area);
+        return;
+    }
+    
+    let latest = &builds[0];
+    let time_str = match latest.finished_at.or(latest.started_at) {
+        Some(at) => build::relative_time(at, chrono::Utc::now()),
+        None => "not started".to_string(),
+    };
+    // Oldest on the left so the strip reads like a timeline.
+    let mut strip = vec![Span::raw("History: ")];
+    strip.extend(
+        builds
+            .iter()
+            .rev()
+            .map(|run| Span::styled("■", status_style(&run.status, theme))),
+    );
+    
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("Status: "),
+            Span::styled(status_indicator(&latest.status), status_style(&latest.status, theme)),
+            Span::raw(format!(" {}", latest.name)),
+        ]),
+        Line::from(format!(
+            "Took: {}, {}",
+            format_duration(build::duration(latest.started_at, latest.finished_at)),
+            time_str
+        )),
+        Line::from(strip),
+    ];
+    for stats in build::workflow_stats(builds) {
+        let rate = stats.success_rate.map_or("-".to_string(), |rate| format!("{:.0}%", rate));
+        let mut spans = vec![Span::raw(format!(
+            "{}: {} ok, avg {}",
+            stats.name,
+            rate,
+            format_duration(stats.average_duration)
+        ))];
+        if stats.flaky {
+            spans.push(Span::styled(" FLAKY", theme.warning().add_modifier(Modifier::BOLD)));
+        }
+        lines.push(Line::from(spans));
+    }
+    if let Some(quota) = rate_limit_text(app) {
+        lines.push(Line::styled(quota, theme.text_secondary()));
+    }
     
-    let widget = widgets::status_box("Build Status", &content, theme, focused);
+    let widget = widgets::status_text("Build Status", Text::from(lines), theme, focused);
     frame.render_widget(widget, area);
 }
 
 fn render_coverage_status(frame: &mut Frame, area: Rect, app: &crate::app::App, theme: &Theme) {
-    let coverage = app.coverage.total_coverage;
+    let coverage = app.coverage.total_coverage();
     let files_count = app.coverage.files.len();
     
     let bar_length: usize = 20;
@@ -157,7 +991,21 @@ fn render_coverage_status(frame: &mut Frame, area: Rect, app: &crate::app::App,
         " ".repeat(bar_length.saturating_sub(filled))
     );
     
-    let content = format!("Coverage: {:.1}%\nFiles: {}\n{}", coverage, files_count, bar);
+    let mut content = format!("Coverage: {:.1}%\nFiles: {}\n{}", coverage, files_count, bar);
+    if let Some(source) = &app.coverage.source {
+        content.push_str(&format!("\nFrom CI: {}", source));
+    }
+    if let Some(patch) = app.patch_coverage.as_ref().filter(|patch| patch.lines.count > 0) {
+        content.push_str(&format!(
+            "\nPatch: {:.1}% ({}/{})",
+            patch.lines.percent(),
+            patch.lines.covered,
+            patch.lines.count
+        ));
+    }
+    if let Some(run) = &app.coverage_run {
+        content.push_str(&format!("\nRunning: {}", run.progress));
+    }
     
     let widget = widgets::status_box("Test Coverage", &content, theme, false);
     frame.render_widget(widget, area);
@@ -177,6 +177,14 @@ impl Theme {
         Style::default().fg(self.primary)
     }
 
+    pub fn secondary(&self) -> Style {
+        Style::default().fg(self.secondary)
+    }
+
+    pub fn accent(&self) -> Style {
+        Style::default().fg(self.accent)
+    }
+
     pub fn success(&self) -> Style {
         Style::default().fg(self.success)
     }
@@ -1,11 +1,20 @@
 use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Style, Modifier},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph, Wrap, BorderType},
 };
 
+use chrono::{Datelike, NaiveDate};
+
 use super::themes::Theme;
 
 pub fn status_box<'a>(title: &'a str, content: &'a str, theme: &Theme, bold_title: bool) -> Paragraph<'a> {
+    status_text(title, Text::from(content), theme, bold_title)
+}
+
+/// Like `status_box`, for content that carries its own styling.
+pub fn status_text<'a>(title: &'a str, content: Text<'a>, theme: &Theme, bold_title: bool) -> Paragraph<'a> {
     let title_style = if bold_title {
         Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
     } else {
@@ -26,6 +35,105 @@ pub fn status_box<'a>(title: &'a str, content: &'a str, theme: &Theme, bold_titl
         .wrap(Wrap { trim: true })
 }
 
+pub fn panel_block<'a>(title: &'a str, theme: &Theme) -> Block<'a> {
+    Block::default()
+        .title(title)
+        .title_style(theme.primary())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary))
+        .style(theme.surface())
+}
+
+/// Renders daily counts as a GitHub-style grid: one row per weekday from
+/// Sunday to Saturday, one column per week, oldest week on the left.
+/// `first_day` is the date of `per_day[0]`.
+pub fn heatmap<'a>(title: &'a str, per_day: &[u64], first_day: NaiveDate, theme: &Theme) -> Paragraph<'a> {
+    let max = per_day.iter().copied().max().unwrap_or(0);
+    
+    let lines: Vec<Line> = weekday_rows(per_day, first_day)
+        .into_iter()
+        .map(|row| {
+            let spans: Vec<Span> = row
+                .into_iter()
+                .map(|count| {
+                    let (glyph, color) = match count {
+                        None => ("  ", theme.text_secondary),
+                        Some(0) => ("· ", theme.text_secondary),
+                        Some(c) if c * 3 <= max => ("░ ", theme.secondary),
+                        Some(c) if c * 3 <= max * 2 => ("▒ ", theme.primary),
+                        _ => ("█ ", theme.success),
+                    };
+                    Span::styled(glyph, Style::default().fg(color))
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+    
+    Paragraph::new(lines).block(panel_block(title, theme))
+}
+
+/// Lays daily counts out by weekday, Sunday first. Days before `first_day`
+/// in its week and after the last day are `None`.
+fn weekday_rows(per_day: &[u64], first_day: NaiveDate) -> Vec<Vec<Option<u64>>> {
+    let offset = first_day.weekday().num_days_from_sunday() as usize;
+    let weeks = (per_day.len() + offset).div_ceil(7);
+    (0..7)
+        .map(|weekday| {
+            (0..weeks)
+                .map(|week| {
+                    (week * 7 + weekday)
+                        .checked_sub(offset)
+                        .and_then(|day| per_day.get(day).copied())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A rectangle of the given width percentage and fixed height, centered in `area`.
+pub fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(height),
+            Constraint::Min(0),
+        ])
+        .split(area);
+    
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+pub fn confirm_dialog<'a>(message: String, hint: &'a str, theme: &Theme) -> Paragraph<'a> {
+    let lines = vec![
+        Line::styled(message, theme.text()),
+        Line::raw(""),
+        Line::styled(hint, theme.text_secondary()),
+    ];
+    
+    Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title("Confirm")
+                .title_style(Style::default().fg(theme.warning).add_modifier(Modifier::BOLD))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(theme.warning))
+                .style(theme.surface()),
+        )
+}
+
 pub fn footer<'a>(theme: &Theme) -> Paragraph<'a> {
     let shortcuts = "[Q]uit | [Tab]Navigate | [Ctrl+T]Theme | [Space]Timer | [R]efresh";
     let branding = "made by moonguip gui기กีギ";
@@ -39,4 +147,22 @@ pub fn footer<'a>(theme: &Theme) -> Paragraph<'a> {
                 .border_style(Style::default().fg(theme.accent))
         )
         .style(theme.text_secondary())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn weekday_rows_start_on_the_first_days_weekday() {
+        // 2025-03-05 is a Wednesday.
+        let first_day = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let per_day: Vec<u64> = (1..=10).collect();
+        let rows = weekday_rows(&per_day, first_day);
+        
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[0], vec![None, Some(5)]);
+        assert_eq!(rows[2], vec![None, Some(7)]);
+        assert_eq!(rows[3], vec![Some(1), Some(8)]);
+        assert_eq!(rows[6], vec![Some(4), None]);
+    }
+}